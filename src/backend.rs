@@ -0,0 +1,125 @@
+//! Selects the arbitrary-precision number types used throughout the crate.
+//!
+//! GMP is used if the `gmp` feature is enabled, otherwise the pure-Rust
+//! `num-bigint` and `num-rational` crates are used.  Both produce identical
+//! results.
+//!
+//! The features are not additive: enabling `gmp` switches `Integer` and
+//! `Rational` to the GMP types even if `bigint` is enabled too, as may
+//! happen when another crate in the dependency graph enables the default
+//! features.  Code that depends on a particular type should name it
+//! explicitly through `RationalNumber` instead.
+
+#[cfg(feature = "gmp")]
+mod imp {
+    use gmp::mpq::Mpq;
+    use gmp::mpz::Mpz;
+
+    /// Arbitrary-precision integer (`gmp::mpz::Mpz`, since the `gmp`
+    /// feature is enabled).
+    pub type Integer = Mpz;
+
+    /// Arbitrary-precision rational number (`gmp::mpq::Mpq`, since the
+    /// `gmp` feature is enabled).
+    pub type Rational = Mpq;
+
+    pub fn ratio(numer: &Integer, denom: &Integer) -> Rational {
+        // Unlike `BigRational::new`, this does not reduce the fraction or
+        // make the denominator positive, which GMP requires of its inputs.
+        let mut q = Mpq::ratio(numer, denom);
+        q.canonicalize();
+        q
+    }
+
+    pub fn numer(q: &Rational) -> Integer {
+        q.get_num()
+    }
+
+    pub fn denom(q: &Rational) -> Integer {
+        q.get_den()
+    }
+
+    pub fn bit_length(x: &Integer) -> usize {
+        x.bit_length()
+    }
+
+    pub fn to_f64(x: &Integer) -> f64 {
+        f64::from(x)
+    }
+
+    pub fn to_i64(x: &Integer) -> Option<i64> {
+        Option::<i64>::from(x)
+    }
+
+    pub fn rem_u64(x: &Integer, m: u64) -> u64 {
+        Option::<u64>::from(&x.modulus(&Mpz::from(m))).unwrap()
+    }
+
+    /// Big-endian bytes of |x|.
+    #[cfg(any(feature = "cache", feature = "rug"))]
+    pub fn to_bytes_be(x: &Integer) -> Vec<u8> {
+        Vec::from(x)
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn from_bytes_be(bytes: &[u8]) -> Integer {
+        Mpz::from(bytes)
+    }
+}
+
+#[cfg(not(feature = "gmp"))]
+mod imp {
+    use num::ToPrimitive;
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+
+    /// Arbitrary-precision integer (`num_bigint::BigInt`, since the `gmp`
+    /// feature is disabled).
+    pub type Integer = BigInt;
+
+    /// Arbitrary-precision rational number (`num_rational::BigRational`,
+    /// since the `gmp` feature is disabled).
+    pub type Rational = BigRational;
+
+    pub fn ratio(numer: &Integer, denom: &Integer) -> Rational {
+        BigRational::new(numer.clone(), denom.clone())
+    }
+
+    pub fn numer(q: &Rational) -> Integer {
+        q.numer().clone()
+    }
+
+    pub fn denom(q: &Rational) -> Integer {
+        q.denom().clone()
+    }
+
+    pub fn bit_length(x: &Integer) -> usize {
+        x.bits() as usize
+    }
+
+    pub fn to_f64(x: &Integer) -> f64 {
+        x.to_f64().unwrap()
+    }
+
+    pub fn to_i64(x: &Integer) -> Option<i64> {
+        x.to_i64()
+    }
+
+    pub fn rem_u64(x: &Integer, m: u64) -> u64 {
+        let r = (x % BigInt::from(m)).to_i128().unwrap();
+        r.rem_euclid(m as i128) as u64
+    }
+
+    /// Big-endian bytes of |x|.
+    #[cfg(any(feature = "cache", feature = "rug"))]
+    pub fn to_bytes_be(x: &Integer) -> Vec<u8> {
+        x.to_bytes_be().1
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn from_bytes_be(bytes: &[u8]) -> Integer {
+        BigInt::from_bytes_be(::num_bigint::Sign::Plus, bytes)
+    }
+}
+
+pub use self::imp::*;
@@ -0,0 +1,48 @@
+extern crate bernoulli_numbers;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "\
+usage: bernoulli <command> <n>
+
+commands:
+    number <n>              print the Bernoulli number B_n
+    irregular-pairs <n>     print the irregular pairs (p, 2k) with p <= n,
+                            one pair per line as `p 2k`
+    irregular-primes <n>    print the irregular primes p <= n, one per line
+";
+
+fn usage() -> ! {
+    eprint!("{}", USAGE);
+    process::exit(2)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() != 2 {
+        usage();
+    }
+    let n: u64 = match args[1].parse() {
+        Ok(n) => n,
+        Err(_) => usage(),
+    };
+    match &args[0][..] {
+        "number" => {
+            println!("{}", bernoulli_numbers::bernoulli(n));
+        }
+        "irregular-pairs" => {
+            for (p, k) in bernoulli_numbers::irregular_pairs(n) {
+                println!("{} {}", p, k);
+            }
+        }
+        "irregular-primes" => {
+            let mut pairs = bernoulli_numbers::irregular_pairs(n);
+            pairs.dedup_by_key(|pair| pair.0);
+            for (p, _) in pairs {
+                println!("{}", p);
+            }
+        }
+        _ => usage(),
+    }
+}
@@ -0,0 +1,281 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use fs2::FileExt;
+use memmap2::Mmap;
+use num::Zero;
+use super::{B1Convention, EvenBernoulli, Rational, backend};
+
+/// Identifies a cache file.
+const MAGIC: &[u8; 8] = b"BERNOULI";
+
+/// Incremented whenever the layout of the cache file changes.
+const VERSION: u32 = 1;
+
+/// Magic number, version, a reserved word, and the number of entries.
+const HEADER_LEN: usize = 24;
+
+/// A table of even-index Bernoulli numbers B₀, B₂, B₄, … that persists in a
+/// binary file and is memory-mapped while in use.  This requires the `cache`
+/// feature.
+///
+/// Looking up a stored entry only decodes that entry.  Asking for an index
+/// beyond the end of the table extends the file to at least twice its
+/// previous length, so the cost of extending it is amortized.
+///
+/// The file starts with a header (the magic number `BERNOULI`, a 32-bit
+/// version, a reserved 32-bit word, and the 64-bit number of entries),
+/// followed by the byte offset of each entry and one past the last, and
+/// finally the entries themselves.  Each entry consists of a sign byte, the
+/// 64-bit length of the numerator, and the magnitudes of the numerator and
+/// denominator in big-endian order.  All other integers are little-endian.
+///
+/// Several processes may share the same file.  They take turns extending it
+/// by holding an advisory lock on a separate file `<path>.lock`, and each
+/// writes the new table to a temporary file in the same directory before
+/// renaming it over the old one.
+///
+///     use bernoulli_numbers::BernoulliCache;
+///
+///     let path = std::env::temp_dir().join("bernoulli_numbers_doctest.bin");
+///     let _ = std::fs::remove_file(&path);
+///     let mut cache = BernoulliCache::open(&path).unwrap();
+///     assert_eq!(cache.get(12).unwrap().to_string(), "-691/2730");
+///     assert_eq!(cache.len(), 7);
+///     drop(cache);
+///
+///     let mut cache = BernoulliCache::open(&path).unwrap();
+///     assert_eq!(cache.len(), 7);
+///     assert_eq!(cache.get(1).unwrap().to_string(), "-1/2");
+///     assert_eq!(cache.get(10).unwrap().to_string(), "5/66");
+///     assert_eq!(cache.get(20).unwrap().to_string(), "-174611/330");
+///     assert_eq!(cache.len(), 14);
+///     std::fs::remove_file(&path).unwrap();
+///
+pub struct BernoulliCache {
+    path: PathBuf,
+    map: Mmap,
+    len: usize,
+}
+
+impl BernoulliCache {
+    /// Opens the cache stored at `path`, creating an empty one if the file
+    /// does not exist.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if the file is not a cache
+    /// file of the current version.
+    ///
+    ///     use bernoulli_numbers::BernoulliCache;
+    ///
+    ///     // Two entries whose offsets 0, 1000, 20 run past the data
+    ///     let mut bytes = b"BERNOULI".to_vec();
+    ///     bytes.extend_from_slice(&1u32.to_le_bytes());
+    ///     bytes.extend_from_slice(&[0; 4]);
+    ///     for &n in &[2u64, 0, 1000, 20] {
+    ///         bytes.extend_from_slice(&n.to_le_bytes());
+    ///     }
+    ///     bytes.extend_from_slice(&[0; 20]);
+    ///     let path = std::env::temp_dir().join("bernoulli_numbers_corrupt.bin");
+    ///     std::fs::write(&path, &bytes).unwrap();
+    ///     let err = BernoulliCache::open(&path).err().unwrap();
+    ///     assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    ///     std::fs::remove_file(&path).unwrap();
+    ///
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            let _lock = lock(&path)?;
+            if !path.exists() {
+                replace_file(&path, &[])?;
+            }
+        }
+        let (map, len) = load(&path)?;
+        Ok(Self { path, map, len })
+    }
+
+    /// Number of even-index Bernoulli numbers stored in the file.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the file contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Looks up Bₙ, using the convention B₁ = −½, and extends the file
+    /// first if it does not contain Bₙ yet.
+    pub fn get(&mut self, n: u64) -> io::Result<Rational> {
+        if n == 1 {
+            return Ok(B1Convention::Minus.b1());
+        }
+        if n % 2 == 1 {
+            return Ok(Rational::zero());
+        }
+        let k = (n / 2) as usize;
+        if k >= self.len {
+            self.extend((k + 1).max(2 * self.len))?;
+        }
+        Ok(decode(self.entry(k)))
+    }
+
+    fn entry(&self, k: usize) -> &[u8] {
+        let data = &self.map[HEADER_LEN + 8 * (self.len + 1)..];
+        let start = read_u64(&self.map, HEADER_LEN + 8 * k) as usize;
+        let end = read_u64(&self.map, HEADER_LEN + 8 * (k + 1)) as usize;
+        &data[start..end]
+    }
+
+    /// Replaces the file with one containing at least `len` entries, unless
+    /// another process has already done so.
+    fn extend(&mut self, len: usize) -> io::Result<()> {
+        let _lock = lock(&self.path)?;
+        let (map, current_len) = load(&self.path)?;
+        self.map = map;
+        self.len = current_len;
+        if self.len >= len {
+            return Ok(());
+        }
+        let mut entries: Vec<_> = (0..self.len)
+            .map(|k| self.entry(k).to_vec())
+            .collect();
+        entries.extend(EvenBernoulli::default()
+                       .skip(self.len)
+                       .take(len - self.len)
+                       .map(|b| encode(&b)));
+        replace_file(&self.path, &entries)?;
+        let (map, len) = load(&self.path)?;
+        self.map = map;
+        self.len = len;
+        Ok(())
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    let mut word = [0; 8];
+    word.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_le_bytes(word)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Acquires the advisory lock for the cache at `path`, which is released
+/// when the returned file is closed.  The cache file itself cannot be locked,
+/// since it is replaced whenever it is extended.
+fn lock(path: &Path) -> io::Result<File> {
+    let mut lock_path = path.to_path_buf().into_os_string();
+    lock_path.push(".lock");
+    let file = OpenOptions::new().write(true).create(true).truncate(false)
+        .open(&lock_path)?;
+    file.lock_exclusive()?;
+    Ok(file)
+}
+
+/// Maps the file and validates its header, offsets, and denominators.
+fn load(path: &Path) -> io::Result<(Mmap, usize)> {
+    let file = File::open(path)?;
+    // SAFETY: The mapping is only sound while the file is not modified.
+    // This crate never writes to a cache file in place: extending it writes
+    // a new file and renames it over the old one, which leaves existing
+    // mappings intact.  Modifying the file by other means is not supported.
+    let map = unsafe { Mmap::map(&file)? };
+    if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+        return Err(invalid_data("not a Bernoulli number cache"));
+    }
+    if map[8..12] != VERSION.to_le_bytes() {
+        return Err(invalid_data("unsupported Bernoulli number cache version"));
+    }
+    let len = read_u64(&map, 16) as usize;
+    let data_start = len.checked_add(1)
+        .and_then(|n| n.checked_mul(8))
+        .and_then(|n| n.checked_add(HEADER_LEN))
+        .filter(|&n| n <= map.len())
+        .ok_or_else(|| invalid_data("truncated Bernoulli number cache"))?;
+    if read_u64(&map, HEADER_LEN) != 0
+        || read_u64(&map, data_start - 8) != (map.len() - data_start) as u64 {
+        return Err(invalid_data("corrupt Bernoulli number cache"));
+    }
+    let data_len = (map.len() - data_start) as u64;
+    for k in 0..len {
+        let start = read_u64(&map, HEADER_LEN + 8 * k);
+        let end = read_u64(&map, HEADER_LEN + 8 * (k + 1));
+        let valid = start.checked_add(9).is_some_and(|n| n <= end)
+            && end <= data_len && {
+            let numer_len = read_u64(&map, data_start + start as usize + 1);
+            numer_len < end - start - 9 && {
+                let denom_start = data_start + (start + 9 + numer_len) as usize;
+                let denom = &map[denom_start..data_start + end as usize];
+                denom.iter().any(|&byte| byte != 0)
+            }
+        };
+        if !valid {
+            return Err(invalid_data("corrupt Bernoulli number cache"));
+        }
+    }
+    Ok((map, len))
+}
+
+/// Atomically replaces the file at `path` with a cache containing `entries`.
+/// The new contents are written to a uniquely named file in the same
+/// directory and then renamed, so that other processes that have the old
+/// file mapped are unaffected and never see a partially written file.
+fn replace_file(path: &Path, entries: &[Vec<u8>]) -> io::Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    let mut tmp_path = path.to_path_buf().into_os_string();
+    tmp_path.push(format!(".{}.{}.{}.tmp", process::id(), nanos,
+                          COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let tmp_path = PathBuf::from(tmp_path);
+    let result = write_file(&tmp_path, entries)
+        .and_then(|()| fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+fn write_file(path: &Path, entries: &[Vec<u8>]) -> io::Result<()> {
+    let mut contents = Vec::new();
+    contents.extend_from_slice(MAGIC);
+    contents.extend_from_slice(&VERSION.to_le_bytes());
+    contents.extend_from_slice(&0u32.to_le_bytes());
+    contents.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+    let mut offset = 0u64;
+    contents.extend_from_slice(&offset.to_le_bytes());
+    for entry in entries {
+        offset += entry.len() as u64;
+        contents.extend_from_slice(&offset.to_le_bytes());
+    }
+    for entry in entries {
+        contents.extend_from_slice(entry);
+    }
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(&contents)?;
+    file.sync_all()
+}
+
+fn encode(b: &Rational) -> Vec<u8> {
+    let numer = backend::numer(b);
+    let numer_bytes = backend::to_bytes_be(&numer);
+    let denom_bytes = backend::to_bytes_be(&backend::denom(b));
+    let mut entry = Vec::with_capacity(9 + numer_bytes.len() + denom_bytes.len());
+    entry.push((numer < Zero::zero()) as u8);
+    entry.extend_from_slice(&(numer_bytes.len() as u64).to_le_bytes());
+    entry.extend_from_slice(&numer_bytes);
+    entry.extend_from_slice(&denom_bytes);
+    entry
+}
+
+fn decode(entry: &[u8]) -> Rational {
+    let numer_len = read_u64(entry, 1) as usize;
+    let numer = backend::from_bytes_be(&entry[9..9 + numer_len]);
+    let denom = backend::from_bytes_be(&entry[9 + numer_len..]);
+    let numer = if entry[0] != 0 { -numer } else { numer };
+    backend::ratio(&numer, &denom)
+}
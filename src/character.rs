@@ -0,0 +1,168 @@
+use num::{One, Zero};
+use super::{BernoulliPolynomial, Integer, Rational};
+
+/// A primitive real Dirichlet character χ modulo its conductor f, that is,
+/// one whose values are 0 and ±1.
+///
+///     use bernoulli_numbers::DirichletCharacter;
+///
+///     // The character modulo 8 with χ(3) = χ(5) = −1
+///     let chi = DirichletCharacter::new(8, &[(3, -1), (5, -1)]);
+///     let values: Vec<_> = (0..8).map(|a| chi.value(a)).collect();
+///     assert_eq!(values, [0, 1, 0, -1, 0, -1, 0, 1]);
+///     assert_eq!(chi.value(-1), 1);
+///     assert!(chi.is_even());
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DirichletCharacter {
+    /// χ(a) for 0 ≤ a < f.
+    values: Vec<i8>,
+}
+
+impl DirichletCharacter {
+    /// Constructs the character of conductor `conductor` from the images
+    /// of generators of the units modulo the conductor, given as pairs
+    /// (g, χ(g)) with χ(g) = ±1.
+    ///
+    /// Panics if the images are not ±1, if the generators are not units or
+    /// do not generate all of them, if the images are inconsistent with the
+    /// relations between the generators, or if the resulting character is
+    /// not primitive.
+    pub fn new(conductor: u64, images: &[(u64, i32)]) -> Self {
+        assert!(conductor > 0, "conductor must be positive");
+        let f = conductor;
+        for &(g, image) in images {
+            assert!(image == 1 || image == -1, "images must be ±1");
+            assert!(gcd(g, f) == 1, "generators must be coprime to the conductor");
+        }
+        let mut values = vec![0i8; f as usize];
+        values[(1 % f) as usize] = 1;
+        let mut stack = vec![1 % f];
+        while let Some(a) = stack.pop() {
+            for &(g, image) in images {
+                let b = (a as u128 * g as u128 % f as u128) as usize;
+                let value = values[a as usize] * image as i8;
+                if values[b] == 0 {
+                    values[b] = value;
+                    stack.push(b as u64);
+                } else {
+                    assert!(values[b] == value, "images do not define a character");
+                }
+            }
+        }
+        assert!((1..f).all(|a| values[a as usize] != 0 || gcd(a, f) != 1),
+                "generators do not generate the units");
+        let chi = Self { values };
+        assert!(chi.is_primitive(), "character is not primitive");
+        chi
+    }
+
+    /// The conductor f.
+    pub fn conductor(&self) -> u64 {
+        self.values.len() as u64
+    }
+
+    /// Evaluates χ(a).
+    pub fn value(&self, a: i64) -> i32 {
+        let f = self.conductor() as i128;
+        i32::from(self.values[(a as i128).rem_euclid(f) as usize])
+    }
+
+    /// Whether χ(−1) = 1.
+    pub fn is_even(&self) -> bool {
+        self.value(-1) == 1
+    }
+
+    /// A character is primitive if, for every prime p dividing f, it is not
+    /// trivial on the units congruent to 1 modulo f / p.
+    fn is_primitive(&self) -> bool {
+        let f = self.conductor();
+        let mut m = f;
+        let mut p = 2;
+        while m > 1 {
+            if p * p > m {
+                p = m;
+            }
+            if m % p != 0 {
+                p += 1;
+                continue;
+            }
+            while m % p == 0 {
+                m /= p;
+            }
+            let d = f / p;
+            if (0..p).all(|k| self.values[((1 + k * d) % f) as usize] != -1) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Calculates the generalized Bernoulli number Bₙ,χ for a primitive real
+/// Dirichlet character χ of conductor f.
+///
+/// These are defined by Bₙ,χ = fⁿ⁻¹ Σₐ χ(a) Bₙ(a / f), summing over
+/// 1 ≤ a ≤ f, where Bₙ(x) is the Bernoulli polynomial.  They give the values
+/// L(1 − n, χ) = −Bₙ,χ / n of Dirichlet L-functions, and vanish unless n
+/// has the same parity as χ, except that B₁,χ = ½ for the trivial character
+/// of conductor 1.
+///
+///     use bernoulli_numbers::{DirichletCharacter, generalized_bernoulli};
+///
+///     // The character modulo 4, for which L(s, χ) = β(s)
+///     let chi = DirichletCharacter::new(4, &[(3, -1)]);
+///     let seq: Vec<_> = (1..8).map(|n| generalized_bernoulli(n, &chi).to_string())
+///         .collect();
+///     assert_eq!(seq, ["-1/2", "0", "3/2", "0", "-25/2", "0", "427/2"]);
+///
+///     let chi = DirichletCharacter::new(5, &[(2, -1)]);
+///     let seq: Vec<_> = (0..7).map(|n| generalized_bernoulli(n, &chi).to_string())
+///         .collect();
+///     assert_eq!(seq, ["0", "0", "4/5", "0", "-8", "0", "804/5"]);
+///
+///     let trivial = DirichletCharacter::new(1, &[]);
+///     assert_eq!(generalized_bernoulli(1, &trivial).to_string(), "1/2");
+///     assert_eq!(generalized_bernoulli(12, &trivial).to_string(), "-691/2730");
+///
+pub fn generalized_bernoulli(n: u64, chi: &DirichletCharacter) -> Rational {
+    // With Bₙ(x) = Σⱼ cⱼ xʲ, this is Σⱼ cⱼ fⁿ⁻ʲ Sⱼ / f, where the
+    // Sⱼ = Σₐ χ(a) aʲ are integers.
+    let f = chi.conductor();
+    let len = n as usize + 1;
+    let mut sums = vec![Integer::from(0u32); len];
+    for a in 1..f + 1 {
+        let value = chi.value(a as i64);
+        if value == 0 {
+            continue;
+        }
+        let mut power: Integer = One::one();
+        for s in &mut sums {
+            if value > 0 {
+                *s += &power;
+            } else {
+                *s -= &power;
+            }
+            power *= Integer::from(a);
+        }
+    }
+    let poly = BernoulliPolynomial::new(n);
+    let mut f_power: Integer = One::one();
+    let total = poly.coefficients().iter().zip(sums).rev()
+        .map(|(c, s)| {
+            let term = c * &Rational::from(s * &f_power);
+            f_power *= Integer::from(f);
+            term
+        })
+        .fold(Zero::zero(), |total: Rational, term| total + &term);
+    total / &Rational::from(Integer::from(f))
+}
@@ -0,0 +1,116 @@
+//! The Euler–Maclaurin formula, which relates a sum to an integral:
+//!
+//! f(a) + f(a + 1) + … + f(b) = ∫ₐᵇ f(x) dx + (f(a) + f(b)) / 2
+//! + Σₖ B₂ₖ / (2k)! (f⁽²ᵏ⁻¹⁾(b) − f⁽²ᵏ⁻¹⁾(a)) + R,
+//!
+//! where k runs from 1 to the order m, and the remainder satisfies
+//! |R| ≤ |B₂ₘ| / (2m)! ∫ₐᵇ |f⁽²ᵐ⁾(x)| dx.
+//!
+//!     use bernoulli_numbers::euler_maclaurin;
+//!
+//!     // 1/5² + 1/6² + … + 1/20², with f⁽ʲ⁾(x) = (−1)ʲ (j + 1)! / xʲ⁺²
+//!     let f = |j: usize, x: f64| {
+//!         let factorial: f64 = (1..j + 2).map(|i| i as f64).product();
+//!         let sign = if j % 2 == 0 { 1.0 } else { -1.0 };
+//!         sign * factorial / x.powi(j as i32 + 2)
+//!     };
+//!     let integral = 1.0 / 5.0 - 1.0 / 20.0;
+//!     let c = euler_maclaurin::corrections(3, 5.0, 20.0, f);
+//!     let exact: f64 = (5..21).map(|i| 1.0 / (i * i) as f64).sum();
+//!     let approx = integral + c.sum();
+//!     assert!((exact - approx).abs() <= c.remainder_bound);
+//!     assert!(c.remainder_bound < 1e-6);
+
+use num::One;
+use super::{EvenBernoulli, Integer, Rational, rational_to_f64};
+
+/// Calculates the coefficients B₂ₖ / (2k)! for k = 1, 2, …, `order`.
+///
+///     use bernoulli_numbers::euler_maclaurin;
+///
+///     let coeffs: Vec<_> = euler_maclaurin::coefficients(3).iter()
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(coeffs, ["1/12", "-1/720", "1/30240"]);
+///
+pub fn coefficients(order: usize) -> Vec<Rational> {
+    let mut factorial: Integer = One::one();
+    EvenBernoulli::default().enumerate().skip(1).take(order).map(|(k, b)| {
+        let k = k as u64;
+        factorial *= Integer::from(2 * k - 1);
+        factorial *= Integer::from(2 * k);
+        b / Rational::from(factorial.clone())
+    }).collect()
+}
+
+/// The terms of the Euler–Maclaurin formula other than the integral.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Corrections {
+    /// The endpoint term (f(a) + f(b)) / 2.
+    pub endpoints: f64,
+    /// The correction terms B₂ₖ / (2k)! (f⁽²ᵏ⁻¹⁾(b) − f⁽²ᵏ⁻¹⁾(a)) for
+    /// k = 1, 2, …, m.
+    pub terms: Vec<f64>,
+    /// A bound on the magnitude of the remainder, which is
+    /// |B₂ₘ| / (2m)! |f⁽²ᵐ⁻¹⁾(b) − f⁽²ᵐ⁻¹⁾(a)|.
+    ///
+    /// This assumes that f⁽²ᵐ⁾ does not change sign on [a, b].  Otherwise,
+    /// the bound is |B₂ₘ| / (2m)! ∫ₐᵇ |f⁽²ᵐ⁾(x)| dx instead.
+    pub remainder_bound: f64,
+}
+
+impl Corrections {
+    /// The sum of the endpoint term and the correction terms, which is to
+    /// be added to the integral.
+    pub fn sum(&self) -> f64 {
+        self.endpoints + self.terms.iter().sum::<f64>()
+    }
+}
+
+/// Calculates the Euler–Maclaurin corrections of order m = `order` for
+/// the sum f(a) + f(a + 1) + … + f(b), where `derivative(j, x)` returns
+/// f⁽ʲ⁾(x).  Only the derivatives of order 0 and 1, 3, …, 2m − 1 are
+/// evaluated, and only at a and b.
+///
+/// Panics if `order` is zero.
+pub fn corrections<F>(order: usize, a: f64, b: f64, mut derivative: F) -> Corrections
+    where F: FnMut(usize, f64) -> f64
+{
+    let mut at_a = vec![0.0; 2 * order];
+    let mut at_b = vec![0.0; 2 * order];
+    for j in (0..2 * order).filter(|&j| j == 0 || j % 2 == 1) {
+        at_a[j] = derivative(j, a);
+        at_b[j] = derivative(j, b);
+    }
+    corrections_from_derivatives(order, &at_a, &at_b)
+}
+
+/// Calculates the Euler–Maclaurin corrections of order m = `order`, given
+/// the derivatives f⁽ʲ⁾(a) = `at_a[j]` and f⁽ʲ⁾(b) = `at_b[j]`.  Only the
+/// entries at j = 0 and j = 1, 3, …, 2m − 1 are used.
+///
+///     use bernoulli_numbers::euler_maclaurin;
+///
+///     // 0³ + 1³ + … + 4³ = 100, where the formula is exact for m = 2
+///     let at_a = [0.0, 0.0, 0.0, 6.0];
+///     let at_b = [64.0, 48.0, 24.0, 6.0];
+///     let c = euler_maclaurin::corrections_from_derivatives(2, &at_a, &at_b);
+///     assert_eq!(64.0 + c.sum(), 100.0);
+///     assert_eq!(c.remainder_bound, 0.0);
+///
+/// Panics if `order` is zero or if the slices have fewer than 2m entries.
+pub fn corrections_from_derivatives(order: usize, at_a: &[f64], at_b: &[f64])
+                                    -> Corrections {
+    assert!(order > 0, "order must be positive");
+    assert!(at_a.len() >= 2 * order && at_b.len() >= 2 * order,
+            "need derivatives up to order {}", 2 * order - 1);
+    let coeffs = coefficients(order);
+    let terms: Vec<f64> = coeffs.iter().enumerate().map(|(i, c)| {
+        let j = 2 * i + 1;
+        rational_to_f64(c) * (at_b[j] - at_a[j])
+    }).collect();
+    Corrections {
+        endpoints: (at_a[0] + at_b[0]) / 2.0,
+        remainder_bound: terms[order - 1].abs(),
+        terms,
+    }
+}
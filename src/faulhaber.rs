@@ -0,0 +1,108 @@
+use num::{One, Zero};
+use super::{B1Convention, Bernoulli, Integer, Rational, backend, is_prime};
+
+/// Faulhaber’s polynomial Sₚ(n) = 1ᵖ + 2ᵖ + … + nᵖ, with exact rational
+/// coefficients.
+///
+/// The polynomial is given by
+/// Sₚ(n) = (Σⱼ C(p + 1, j) Bⱼ nᵖ⁺¹⁻ʲ) / (p + 1), summing over 0 ≤ j ≤ p
+/// with the convention B₁ = +½.
+///
+///     use bernoulli_numbers::{FaulhaberPolynomial, Integer};
+///
+///     // S₂(n) = n³/3 + n²/2 + n/6
+///     let s = FaulhaberPolynomial::new(2);
+///     let coeffs: Vec<_> = s.coefficients().iter()
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(coeffs, ["0", "1/6", "1/2", "1/3"]);
+///     assert_eq!(s.eval(&Integer::from(10)).to_string(), "385");
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FaulhaberPolynomial {
+    coeffs: Vec<Rational>,
+    /// The coefficients multiplied by `denom`, which makes them integers.
+    scaled: Vec<Integer>,
+    denom: Integer,
+}
+
+impl FaulhaberPolynomial {
+    /// Constructs Sₚ(n).
+    pub fn new(p: u64) -> Self {
+        let len = p as usize + 2;
+        let mut coeffs = vec![Zero::zero(); len];
+        let mut binomial: Integer = One::one();
+        let p1 = Rational::from(Integer::from(p + 1));
+        let bs = Bernoulli::new(B1Convention::Plus).take(len - 1);
+        for (j, b) in bs.enumerate() {
+            coeffs[len - 1 - j] = Rational::from(binomial.clone()) * b / &p1;
+            let j = j as u64;
+            binomial = binomial * Integer::from(p + 1 - j) / Integer::from(j + 1);
+        }
+        // By the von Staudt–Clausen theorem, every denominator divides p + 1
+        // times the product of the primes up to p + 1.
+        let mut denom = Integer::from(p + 1);
+        for q in 2..p + 2 {
+            if is_prime(q) {
+                denom *= Integer::from(q);
+            }
+        }
+        let scaled = coeffs.iter()
+            .map(|c| backend::numer(c) * (&denom / backend::denom(c)))
+            .collect();
+        Self { coeffs, scaled, denom }
+    }
+
+    /// The degree p + 1 of the polynomial.
+    pub fn degree(&self) -> u64 {
+        self.coeffs.len() as u64 - 1
+    }
+
+    /// The coefficients in order of increasing powers of `n`.
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coeffs
+    }
+
+    /// Evaluates the polynomial exactly.  This only uses integer arithmetic,
+    /// so it takes O(p) multiplications regardless of the size of `n`.
+    pub fn eval(&self, n: &Integer) -> Integer {
+        let mut coeffs = self.scaled.iter().rev();
+        let mut accum = coeffs.next().unwrap().clone();
+        for c in coeffs {
+            accum = accum * n + c;
+        }
+        accum / &self.denom
+    }
+}
+
+/// Calculates the power sum 1ᵖ + 2ᵖ + … + nᵖ, which is zero if n ≤ 0.
+///
+/// For n ≤ p, this adds up the powers directly.  Otherwise, it evaluates
+/// `FaulhaberPolynomial`, whose cost does not depend on the size of `n`.
+///
+///     use bernoulli_numbers::{Integer, power_sum};
+///
+///     assert_eq!(power_sum(3, &Integer::from(100)).to_string(), "25502500");
+///     assert_eq!(power_sum(10, &Integer::from(1000)).to_string(),
+///                "91409924241424243424241924242500");
+///     assert_eq!(power_sum(0, &Integer::from(-5)).to_string(), "0");
+///
+///     // 1² + 2² + … + n² = n (n + 1) (2n + 1) / 6
+///     let n = Integer::from(10).pow(30);
+///     let expected = &n * (&n + Integer::from(1))
+///         * (&n * Integer::from(2) + Integer::from(1)) / Integer::from(6);
+///     assert_eq!(power_sum(2, &n), expected);
+///
+pub fn power_sum(p: u64, n: &Integer) -> Integer {
+    if *n <= Integer::from(0u32) {
+        return Zero::zero();
+    }
+    if *n <= Integer::from(p) {
+        let n = backend::to_i64(n).unwrap() as u64;
+        let mut sum: Integer = Zero::zero();
+        for k in 1..n + 1 {
+            sum += Integer::from(k).pow(p as u32);
+        }
+        return sum;
+    }
+    FaulhaberPolynomial::new(p).eval(n)
+}
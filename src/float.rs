@@ -0,0 +1,488 @@
+use std::ops::RangeInclusive;
+use super::{Integer, backend, bernoulli, bit_length, staudt_clausen_primes, exp_fixed, ln2_fixed,
+            ln_factorial_fixed, ln_fixed, pi_fixed, zeta_fixed};
+
+/// B₀, B₂, …, B₂₅₈ correctly rounded to `f64`.  The magnitude of B₂₆₀ and
+/// all later even-index Bernoulli numbers exceeds `f64::MAX`.
+const EVEN_BERNOULLI_F64: [f64; 130] = [
+    1.0,
+    0.16666666666666666,
+    -0.03333333333333333,
+    0.023809523809523808,
+    -0.03333333333333333,
+    0.07575757575757576,
+    -0.2531135531135531,
+    1.1666666666666667,
+    -7.092156862745098,
+    54.971177944862156,
+    -529.1242424242424,
+    6192.123188405797,
+    -86580.25311355312,
+    1425517.1666666667,
+    -27298231.067816094,
+    601580873.9006424,
+    -15116315767.092157,
+    429614643061.1667,
+    -13711655205088.332,
+    488332318973593.2,
+    -1.9296579341940068e+16,
+    8.416930475736826e+17,
+    -4.0338071854059454e+19,
+    2.1150748638081993e+21,
+    -1.2086626522296526e+23,
+    7.500866746076964e+24,
+    -5.038778101481069e+26,
+    3.6528776484818122e+28,
+    -2.849876930245088e+30,
+    2.3865427499683627e+32,
+    -2.1399949257225335e+34,
+    2.0500975723478097e+36,
+    -2.093800591134638e+38,
+    2.2752696488463515e+40,
+    -2.6257710286239577e+42,
+    3.212508210271803e+44,
+    -4.159827816679471e+46,
+    5.692069548203528e+48,
+    -8.218362941978458e+50,
+    1.2502904327166994e+53,
+    -2.001558323324837e+55,
+    3.3674982915364376e+57,
+    -5.947097050313545e+59,
+    1.1011910323627977e+62,
+    -2.1355259545253502e+64,
+    4.3328896986641194e+66,
+    -9.188552824166933e+68,
+    2.0346896776329074e+71,
+    -4.700383395803573e+73,
+    1.131804344548425e+76,
+    -2.8382249570693707e+78,
+    7.406424897967885e+80,
+    -2.0096454802756605e+83,
+    5.665717005080594e+85,
+    -1.6584511154136216e+88,
+    5.036885995049238e+90,
+    -1.5861468237658186e+93,
+    5.1756743617545625e+95,
+    -1.7488921840217116e+98,
+    6.116051999495218e+100,
+    -2.2122776912707833e+103,
+    8.272277679877097e+105,
+    -3.195892511141571e+108,
+    1.2750082223387793e+111,
+    -5.250092308677413e+113,
+    2.2301817894241627e+116,
+    -9.76845219309552e+118,
+    4.409836197845295e+121,
+    -2.050857088646409e+124,
+    9.821443327979128e+126,
+    -4.841260079820888e+129,
+    2.4553088801480982e+132,
+    -1.2806926804084748e+135,
+    6.867616710466858e+137,
+    -3.7846468581969106e+140,
+    2.142610125066529e+143,
+    -1.2456727137183695e+146,
+    7.434578755100016e+148,
+    -4.5535795304641704e+151,
+    2.861211281685887e+154,
+    -1.843772355203387e+157,
+    1.2181154536221047e+160,
+    -8.248218718531412e+162,
+    5.722587793783294e+165,
+    -4.0668530525059105e+168,
+    2.9596092064642052e+171,
+    -2.2049522565189457e+174,
+    1.68125970728896e+177,
+    -1.3116736213556958e+180,
+    1.0467894009478039e+183,
+    -8.543289357883371e+185,
+    7.128782132248655e+188,
+    -6.08029314555359e+191,
+    5.299677642484992e+194,
+    -4.719425916874586e+197,
+    4.292841379140298e+200,
+    -3.9876744968232205e+203,
+    3.781978041935888e+206,
+    -3.661423368368119e+209,
+    3.617609027237286e+212,
+    -3.647077264519136e+215,
+    3.750875543645441e+218,
+    -3.934586729643903e+221,
+    4.208821114819008e+224,
+    -4.590229622061792e+227,
+    5.103172577262957e+230,
+    -5.782276230365695e+233,
+    6.676248216783588e+236,
+    -7.853530764445042e+239,
+    9.410689406705872e+242,
+    -1.1484933873465185e+246,
+    1.4272958742848785e+249,
+    -1.805955958690931e+252,
+    2.3261535307660807e+255,
+    -3.0495751715499594e+258,
+    4.068580607643398e+261,
+    -5.523103132197436e+264,
+    7.6277279396434395e+267,
+    -1.0715571119697886e+271,
+    1.5310200895969188e+274,
+    -2.2244891682179836e+277,
+    3.286267919069014e+280,
+    -4.935592895596035e+283,
+    7.534957120083251e+286,
+    -1.1691485154584178e+290,
+    1.843526146783894e+293,
+    -2.953682617296808e+296,
+    4.807932127750157e+299,
+    -7.950212504588525e+302,
+    1.3352784187354634e+306,
+];
+
+/// B₀, B₂, …, B₆₄ correctly rounded to `f32`.  The magnitude of B₆₆ and all
+/// later even-index Bernoulli numbers exceeds `f32::MAX`.
+const EVEN_BERNOULLI_F32: [f32; 33] = [
+    1.0,
+    0.16666667,
+    -0.033333335,
+    0.023809524,
+    -0.033333335,
+    0.07575758,
+    -0.25311357,
+    1.1666666,
+    -7.092157,
+    54.971176,
+    -529.12427,
+    6192.123,
+    -86580.25,
+    1425517.1,
+    -27298232.0,
+    6.0158086e+08,
+    -1.5116316e+10,
+    4.2961463e+11,
+    -1.3711655e+13,
+    4.883323e+14,
+    -1.929658e+16,
+    8.4169306e+17,
+    -4.0338073e+19,
+    2.1150749e+21,
+    -1.20866265e+23,
+    7.500867e+24,
+    -5.038778e+26,
+    3.6528777e+28,
+    -2.849877e+30,
+    2.3865428e+32,
+    -2.139995e+34,
+    2.0500976e+36,
+    -2.0938006e+38,
+];
+
+/// Sign of the even-index Bernoulli number Bₙ, where `n > 0`.
+fn is_negative(n: u64) -> bool {
+    n % 4 == 0
+}
+
+/// Calculates Bₙ correctly rounded to `f64`, using the convention B₁ = −½.
+/// Beyond B₂₅₈, the magnitude is too large and the result is ±∞; use
+/// `bernoulli_frexp` to obtain the exponent in that case.
+///
+///     use bernoulli_numbers::bernoulli_f64;
+///
+///     assert_eq!(bernoulli_f64(1), -0.5);
+///     assert_eq!(bernoulli_f64(12), -691.0 / 2730.0);
+///     assert_eq!(bernoulli_f64(13), 0.0);
+///     assert_eq!(bernoulli_f64(258), 1.3352784187354634e306);
+///     assert_eq!(bernoulli_f64(260), -f64::INFINITY);
+///     assert_eq!(bernoulli_f64(1 << 40), -f64::INFINITY);
+///
+pub fn bernoulli_f64(n: u64) -> f64 {
+    if n == 1 {
+        return -0.5;
+    }
+    if n % 2 == 1 {
+        return 0.0;
+    }
+    match EVEN_BERNOULLI_F64.get((n / 2) as usize) {
+        Some(&b) => b,
+        None if is_negative(n) => -f64::INFINITY,
+        None => f64::INFINITY,
+    }
+}
+
+/// Calculates Bₙ correctly rounded to `f32`, using the convention B₁ = −½.
+/// Beyond B₆₄, the magnitude is too large and the result is ±∞.
+///
+///     use bernoulli_numbers::bernoulli_f32;
+///
+///     assert_eq!(bernoulli_f32(2), 1.0 / 6.0);
+///     assert_eq!(bernoulli_f32(64), -2.0938006e38);
+///     assert_eq!(bernoulli_f32(66), f32::INFINITY);
+///
+pub fn bernoulli_f32(n: u64) -> f32 {
+    if n == 1 {
+        return -0.5;
+    }
+    if n % 2 == 1 {
+        return 0.0;
+    }
+    match EVEN_BERNOULLI_F32.get((n / 2) as usize) {
+        Some(&b) => b,
+        None if is_negative(n) => -f32::INFINITY,
+        None => f32::INFINITY,
+    }
+}
+
+/// Calculates bounds on log₂ |Bₙ|, using the convention B₁ = −½.  The
+/// bounds are guaranteed, and their relative difference is at most about
+/// 10⁻¹³.  For the odd-index zeros, both bounds are −∞.
+///
+/// This takes constant time, so it is useful for estimating the cost of an
+/// exact calculation before starting it.
+///
+///     use bernoulli_numbers::{bernoulli_frexp, bernoulli_log2_abs};
+///
+///     assert_eq!(bernoulli_log2_abs(0), 0.0..=0.0);
+///     assert_eq!(bernoulli_log2_abs(1), -1.0..=-1.0);
+///     for &n in &[2, 12, 100, 1000, 1 << 40] {
+///         let (m, e) = bernoulli_frexp(n);
+///         let bounds = bernoulli_log2_abs(n);
+///         assert!(bounds.contains(&(m.abs().log2() + e as f64)));
+///         assert!(bounds.end() - bounds.start() < 1e-12 * bounds.end().abs());
+///     }
+///
+pub fn bernoulli_log2_abs(n: u64) -> RangeInclusive<f64> {
+    use std::f64::consts::{LN_2, PI};
+    if n == 0 {
+        return 0.0..=0.0;
+    }
+    if n == 1 {
+        return -1.0..=-1.0;
+    }
+    if n % 2 == 1 {
+        return f64::NEG_INFINITY..=f64::NEG_INFINITY;
+    }
+    let b = bernoulli_f64(n).abs();
+    if b.is_finite() {
+        // The table entry is correctly rounded, so it has a relative error of
+        // at most 2⁻⁵³.
+        let log2_b = b.log2();
+        let margin = 1e-15 * (log2_b.abs() + 1.0);
+        return log2_b - margin..=log2_b + margin;
+    }
+    let x = n as f64;
+    // ln |Bₙ| = ln(2) + ln(n!) − n ln(2π) + ln(ζ(n)), where
+    // ln(n!) = (n + ½) ln(n) − n + ½ ln(2π) + r by Stirling’s series, with
+    // 1 / (12n) − 1 / (360n³) < r < 1 / (12n) − 1 / (360n³) + 1 / (1260n⁵),
+    // and 0 < ln(ζ(n)) < ζ(n) − 1 < 3 / 2ⁿ.
+    let ln_2pi = (2.0 * PI).ln();
+    let base = LN_2 + (x + 0.5) * x.ln() - x + 0.5 * ln_2pi - x * ln_2pi
+        + 1.0 / (12.0 * x) - 1.0 / (360.0 * x.powi(3));
+    let lower = base;
+    let upper = base + 1.0 / (1260.0 * x.powi(5)) + 3.0 * (-x).exp2();
+    // Allow for the rounding errors of the floating-point evaluation, which
+    // are at most a few units in the last place of the largest term.
+    let margin = 1e-14 * (x + 1.0) * (x.ln() + 3.0);
+    (lower - margin) / LN_2..=(upper + margin) / LN_2
+}
+
+/// Calculates bounds on the number of bits in the numerator of Bₙ (in
+/// lowest terms, and not counting the sign).  The bounds are guaranteed,
+/// and are usually equal.
+///
+/// This takes O(√n) time, to find the denominator from the von
+/// Staudt–Clausen theorem.
+///
+///     use bernoulli_numbers::{bernoulli_numerator_bits, bernoulli_numerator};
+///
+///     assert_eq!(bernoulli_numerator_bits(3), 0..=0);
+///     assert_eq!(bernoulli_numerator_bits(12), 10..=10);
+///     assert_eq!(bernoulli_numerator_bits(1000), 5908..=5908);
+///     let bits = bernoulli_numerator_bits(1_000_000_000);
+///     assert_eq!(bits, 25803161856..=25803161856);
+///
+pub fn bernoulli_numerator_bits(n: u64) -> RangeInclusive<u64> {
+    if n < 2 {
+        return 1..=1;
+    }
+    if n % 2 == 1 {
+        return 0..=0;
+    }
+    let primes = staudt_clausen_primes(n);
+    let log2_denom: f64 = primes.iter().map(|&p| (p as f64).log2()).sum();
+    let margin = 1e-14 * (primes.len() as f64) * log2_denom;
+    let log2_abs = bernoulli_log2_abs(n);
+    let lower = log2_abs.start() + log2_denom - margin;
+    let upper = log2_abs.end() + log2_denom + margin;
+    (lower.floor() as u64 + 1)..=(upper.floor() as u64 + 1)
+}
+
+/// Calculates Bₙ as a mantissa `m` and an exponent `e` such that
+/// Bₙ = m · 2ᵉ, where ½ ≤ |m| < 1 is correctly rounded to `f64` (or
+/// m = e = 0 if Bₙ = 0).  Unlike `bernoulli_f64`, this works for all `n`.
+///
+/// Beyond the range of `f64`, this uses |Bₙ| = 2 n! ζ(n) / (2π)ⁿ, with
+/// ln(n!) from Stirling’s series, evaluated in fixed-point arithmetic.  The
+/// precision is increased until the rounding is unambiguous.
+///
+///     use bernoulli_numbers::{bernoulli_f64, bernoulli_frexp};
+///
+///     assert_eq!(bernoulli_frexp(3), (0.0, 0));
+///     assert_eq!(bernoulli_frexp(12), (-691.0 / 2730.0 * 2.0, -1));
+///     let (m, e) = bernoulli_frexp(258);
+///     assert_eq!(m * 2f64.powi(e as i32), bernoulli_f64(258));
+///     // B₁₀₀₀ ≈ −5.318704469415522e1769
+///     assert_eq!(bernoulli_frexp(1000), (-0.9342462238400226, 5879));
+///
+/// Panics if the exponent does not fit into `i64`.
+pub fn bernoulli_frexp(n: u64) -> (f64, i64) {
+    let b = bernoulli_f64(n);
+    if b == 0.0 {
+        return (0.0, 0);
+    }
+    if b.is_finite() {
+        // Bernoulli numbers are never subnormal.
+        let bits = b.to_bits();
+        let e = ((bits >> 52) & 0x7ff) as i64 - 1022;
+        let m = f64::from_bits((bits & !(0x7ff << 52)) | (1022 << 52));
+        return (m, e);
+    }
+    let (negative, m, e) = bernoulli_rounded(n, 53);
+    let m = backend::to_f64(&m) / (1u64 << 53) as f64;
+    (if negative { -m } else { m }, e + 53)
+}
+
+/// Calculates Bₙ to `prec` bits as an MPFR floating-point number, using
+/// the convention B₁ = −½.  This requires the `rug` feature.
+///
+/// The result is correctly rounded to nearest.  For small `n`, it is
+/// rounded from the exact rational.  Otherwise, it is evaluated from
+/// B₂ₖ = (−1)ᵏ⁺¹ 2 (2k)! ζ(2k) / (2π)²ᵏ with rigorous error bounds, and the
+/// working precision is increased until the rounding is unambiguous.
+///
+///     # #[cfg(feature = "rug")] {
+///     use bernoulli_numbers::bernoulli_float;
+///
+///     assert_eq!(bernoulli_float(0, 53).to_f64(), 1.0);
+///     assert_eq!(bernoulli_float(12, 100).prec(), 100);
+///     assert_eq!(bernoulli_float(12, 53).to_f64(), -691.0 / 2730.0);
+///     let b = bernoulli_float(1000, 53);
+///     assert_eq!(b.to_f64_exp(), (-0.9342462238400226, 5879));
+///     # }
+///
+/// Panics if the exponent lies outside the range of MPFR.
+#[cfg(feature = "rug")]
+pub fn bernoulli_float(n: u64, prec: u32) -> ::rug::Float {
+    use std::convert::TryFrom;
+    use rug::Float;
+    use rug::integer::Order;
+    if n == 0 {
+        return Float::with_val(prec, 1);
+    }
+    if n == 1 {
+        return Float::with_val(prec, -0.5);
+    }
+    if n % 2 == 1 {
+        return Float::new(prec);
+    }
+    let (negative, m, e) = bernoulli_rounded(n, prec as usize);
+    // MPFR normalizes the mantissa to [½, 1), so the exponent of m · 2ᵉ is
+    // e + prec.  Shifting beyond the exponent range would silently overflow
+    // to infinity or underflow to zero.
+    let exp = e + prec as i64;
+    assert!(exp <= i64::from(rug::float::exp_max()) && exp >= i64::from(rug::float::exp_min()),
+            "exponent out of range");
+    let m = rug::Integer::from_digits(&backend::to_bytes_be(&m), Order::Msf);
+    let e = i32::try_from(e).expect("exponent out of range");
+    let b = Float::with_val(prec, m) << e;
+    if negative { -b } else { b }
+}
+
+/// Indices below this plus `prec / 8` are rounded from the exact rational,
+/// because the direct summation of ζ(n) would need too many terms.
+const EXACT_FLOAT_THRESHOLD: u64 = 64;
+
+/// Calculates Bₙ for even `n > 0` as a sign, a mantissa m ∈ [2ᵖ⁻¹, 2ᵖ)
+/// where p = `prec`, and an exponent e, such that |Bₙ| ≈ m · 2ᵉ is correctly
+/// rounded to nearest.
+fn bernoulli_rounded(n: u64, prec: usize) -> (bool, Integer, i64) {
+    debug_assert!(n > 0 && n % 2 == 0);
+    if n < EXACT_FLOAT_THRESHOLD + prec as u64 / 8 {
+        let b = bernoulli(n);
+        let (m, e) = round_ratio(&backend::numer(&b), &backend::denom(&b), prec);
+        return (is_negative(n), m, e);
+    }
+    // Bₙ is never a dyadic rational (its denominator is divisible by 6), so
+    // it never lies exactly halfway and this loop always terminates.
+    let mut guard_bits = 32;
+    loop {
+        if let Some((m, e)) = bernoulli_rounded_approx(n, prec, guard_bits) {
+            return (is_negative(n), m, e);
+        }
+        guard_bits *= 2;
+    }
+}
+
+/// Rounds numer / denom to nearest, where both are positive, returning a
+/// mantissa m ∈ [2ᵖ⁻¹, 2ᵖ) where p = `prec` and an exponent e.
+fn round_ratio(numer: &Integer, denom: &Integer, prec: usize) -> (Integer, i64) {
+    let numer = if *numer < Integer::from(0u32) { -numer.clone() } else { numer.clone() };
+    // Aim for a quotient with prec + 1 or prec + 2 bits, then round off the
+    // last one or two.
+    let shift = prec as i64 + 2 - backend::bit_length(&numer) as i64
+        + backend::bit_length(denom) as i64;
+    let (a, b) = if shift >= 0 {
+        (numer << shift as usize, denom.clone())
+    } else {
+        (numer, denom.clone() << (-shift) as usize)
+    };
+    let q = &a / &b;
+    let exact = (&q * &b) == a;
+    let extra = backend::bit_length(&q) - prec;
+    let mut m: Integer = &q >> extra;
+    let rest = q - (m.clone() << extra);
+    let half = Integer::from(1u32) << (extra - 1);
+    let odd = backend::rem_u64(&m, 2) == 1;
+    if rest > half || (rest == half && (!exact || odd)) {
+        m += Integer::from(1u32);
+    }
+    let e = extra as i64 - shift;
+    if backend::bit_length(&m) > prec {
+        (m >> 1, e + 1)
+    } else {
+        (m, e)
+    }
+}
+
+/// Calculates the mantissa and exponent of |Bₙ| for even `n > 0`, as in
+/// `bernoulli_rounded`, or `None` if the rounding cannot be decided at this
+/// working precision.
+fn bernoulli_rounded_approx(n: u64, prec: usize, guard_bits: usize)
+                            -> Option<(Integer, i64)> {
+    // Each of the logarithms below is accurate to a few units in the last
+    // place, but ln(n!) and n ln(2π) multiply their errors by about n.
+    let error_bits = bit_length(n) + 8;
+    let bits = prec + error_bits + guard_bits;
+    let ln2 = ln2_fixed(bits);
+    // ln |Bₙ| = ln(2) + ln(n!) − n ln(2π) + ln(ζ(n))
+    let mut ln_b = &ln2 + ln_factorial_fixed(n, bits)
+        - Integer::from(n) * ln_fixed(&(pi_fixed(bits) << 1), bits);
+    if (n as usize) < bits + 2 {
+        ln_b += ln_fixed(&zeta_fixed(n, bits), bits);
+    }
+    let log2_b = (ln_b << bits) / &ln2;
+    let e: Integer = &log2_b >> bits;
+    let fraction = log2_b - (e.clone() << bits);
+    let m = exp_fixed(&((fraction * &ln2) >> bits), bits);
+    // Round to nearest, where m ∈ [1, 2) carries `bits` fractional bits.
+    let error = Integer::from(1u32) << error_bits;
+    let half = Integer::from(1u32) << (bits - prec);
+    let lower: Integer = (&m - &error + &half) >> (bits - prec + 1);
+    let upper: Integer = (&m + &error + &half) >> (bits - prec + 1);
+    if lower != upper {
+        return None;
+    }
+    let e = backend::to_i64(&e).expect("exponent out of range") + 1 - prec as i64;
+    Some(if backend::bit_length(&upper) > prec {
+        (upper >> 1, e + 1)
+    } else {
+        (upper, e)
+    })
+}
@@ -0,0 +1,285 @@
+use super::{EvenBernoulli, Integer, Rational, factorial, rational_to_f64};
+
+/// The coefficients B₂ₖ / (2k (2k − 1)) of the Stirling series
+/// ln Γ(z) ~ (z − ½) ln z − z + ½ ln 2π + Σₖ B₂ₖ / (2k (2k − 1) z²ᵏ⁻¹),
+/// for k = 1, 2, ….
+///
+/// Note: This is an infinite iterator.
+///
+///     use bernoulli_numbers::StirlingCoefficients;
+///
+///     let seq: Vec<_> = StirlingCoefficients::default().take(6)
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(seq, ["1/12", "-1/360", "1/1260", "-1/1680", "1/1188",
+///                      "-691/360360"]);
+///
+#[derive(Default)]
+pub struct StirlingCoefficients {
+    k: u64,
+    evens: EvenBernoulli,
+}
+
+impl Iterator for StirlingCoefficients {
+    type Item = Rational;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.k == 0 {
+            self.evens.next()?;
+        }
+        self.k += 1;
+        let k = self.k;
+        let b = self.evens.next()?;
+        Some(b / Rational::from(Integer::from(2 * k * (2 * k - 1))))
+    }
+}
+
+/// Evaluates the Stirling series for ln Γ(z) with the given number of
+/// terms of `StirlingCoefficients`.  Returns the result along with a bound
+/// on the truncation error.
+///
+/// For z > 0, the truncation error has the same sign as the first omitted
+/// term and is smaller in magnitude, so the bound is the magnitude of that
+/// term.  The bound does not account for rounding errors.  The series
+/// diverges, so adding more terms only helps while they keep decreasing,
+/// which is roughly while the number of terms is less than πz.
+///
+///     use bernoulli_numbers::lgamma_stirling;
+///
+///     // ln Γ(5) = ln 24
+///     let (value, bound) = lgamma_stirling(5.0, 4);
+///     assert!((value - 24f64.ln()).abs() <= bound);
+///     assert!(bound < 1e-9);
+///
+/// Panics unless z > 0.
+pub fn lgamma_stirling(z: f64, terms: usize) -> (f64, f64) {
+    assert!(z > 0.0, "z must be positive");
+    let mut coeffs = StirlingCoefficients::default();
+    let z2 = z * z;
+    let mut power = 1.0 / z;
+    let mut sum = (z - 0.5) * z.ln() - z + 0.5 * (2.0 * ::std::f64::consts::PI).ln();
+    for c in coeffs.by_ref().take(terms) {
+        sum += rational_to_f64(&c) * power;
+        power /= z2;
+    }
+    let next = coeffs.next().unwrap();
+    (sum, rational_to_f64(&next).abs() * power)
+}
+
+/// Evaluates the Stirling series for ln Γ(z) with the given number of
+/// terms at the precision of `z`, as `lgamma_stirling` does.  Returns the
+/// result along with a bound on the truncation error.  This requires the
+/// `rug` feature.
+///
+///     # #[cfg(feature = "rug")] {
+///     extern crate rug;
+///     use bernoulli_numbers::lgamma_stirling_float;
+///
+///     // ln Γ(30) = ln 29!
+///     let z = rug::Float::with_val(200, 30);
+///     let (value, bound) = lgamma_stirling_float(&z, 20);
+///     let factorial = rug::Integer::from(rug::Integer::factorial(29));
+///     let exact = rug::Float::with_val(200, factorial).ln();
+///     assert!(rug::Float::with_val(200, value - exact).abs() <= bound);
+///     assert!(bound < 1e-40);
+///     # }
+///
+/// Panics unless z > 0.
+#[cfg(feature = "rug")]
+pub fn lgamma_stirling_float(z: &::rug::Float, terms: usize) -> (::rug::Float, ::rug::Float) {
+    use rug::Float;
+    use rug::float::Constant;
+    assert!(*z > 0, "z must be positive");
+    let prec = z.prec();
+    let two_pi = Float::with_val(prec, Constant::Pi) * 2u32;
+    let mut sum = Float::with_val(prec, z - 0.5f64) * Float::with_val(prec, z.ln_ref());
+    sum -= z;
+    sum += two_pi.ln() / 2u32;
+    let mut coeffs = StirlingCoefficients::default();
+    let z2 = Float::with_val(prec, z.square_ref());
+    let mut power = Float::with_val(prec, z.recip_ref());
+    for c in coeffs.by_ref().take(terms) {
+        sum += Float::with_val(prec, &to_rug_rational(&c)) * &power;
+        power /= &z2;
+    }
+    let next = Float::with_val(prec, &to_rug_rational(&coeffs.next().unwrap()));
+    (sum, next.abs() * power)
+}
+
+#[cfg(feature = "rug")]
+fn to_rug_rational(q: &Rational) -> ::rug::Rational {
+    use rug::integer::Order;
+    use super::backend;
+    let numer = backend::numer(q);
+    let negative = numer < Integer::from(0u32);
+    let numer = rug::Integer::from_digits(&backend::to_bytes_be(&numer), Order::Msf);
+    let denom = rug::Integer::from_digits(&backend::to_bytes_be(&backend::denom(q)),
+                                          Order::Msf);
+    let q = rug::Rational::from((numer, denom));
+    if negative { -q } else { q }
+}
+
+/// The coefficients B₂ₖ (2k + m − 1)! / (2k)! = B₂ₖ (2k + 1)(2k + 2)⋯(2k + m − 1)
+/// of the asymptotic expansion of the polygamma function ψ⁽ᵐ⁾(z), for
+/// k = 1, 2, ….  For m = 0, they are B₂ₖ / 2k.
+///
+/// The expansions are
+///
+/// ψ(z) ~ ln z − 1 / 2z − Σₖ B₂ₖ / (2k z²ᵏ),
+///
+/// ψ⁽ᵐ⁾(z) ~ (−1)ᵐ⁺¹ ((m − 1)! / zᵐ + m! / (2 zᵐ⁺¹)
+/// + Σₖ B₂ₖ (2k + m − 1)! / ((2k)! z²ᵏ⁺ᵐ)) for m ≥ 1.
+///
+/// Note: This is an infinite iterator.
+///
+///     use bernoulli_numbers::PolygammaCoefficients;
+///
+///     let digamma: Vec<_> = PolygammaCoefficients::new(0).take(4)
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(digamma, ["1/12", "-1/120", "1/252", "-1/240"]);
+///
+///     let trigamma: Vec<_> = PolygammaCoefficients::new(1).take(4)
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(trigamma, ["1/6", "-1/30", "1/42", "-1/30"]);
+///
+///     let tetragamma: Vec<_> = PolygammaCoefficients::new(2).take(4)
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(tetragamma, ["1/2", "-1/6", "1/6", "-3/10"]);
+///
+pub struct PolygammaCoefficients {
+    m: u64,
+    k: u64,
+    evens: EvenBernoulli,
+}
+
+impl PolygammaCoefficients {
+    /// Creates the sequence for ψ⁽ᵐ⁾.
+    pub fn new(m: u32) -> Self {
+        let mut evens = EvenBernoulli::default();
+        evens.next();
+        Self { m: m as u64, k: 0, evens }
+    }
+}
+
+impl Iterator for PolygammaCoefficients {
+    type Item = Rational;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.k += 1;
+        let n = 2 * self.k;
+        let b = self.evens.next()?;
+        if self.m == 0 {
+            return Some(b / Rational::from(Integer::from(n)));
+        }
+        let mut rising = Integer::from(1u64);
+        for j in n + 1..n + self.m {
+            rising *= Integer::from(j);
+        }
+        Some(b * Rational::from(rising))
+    }
+}
+
+/// Calculates the digamma function ψ(z) = Γ′(z) / Γ(z) for z > 0.  This is
+/// the same as `polygamma(0, z)`.
+///
+///     use bernoulli_numbers::digamma;
+///
+///     // ψ(1) = −γ and ψ(½) = −γ − 2 ln 2
+///     let gamma = 0.5772156649015329;
+///     assert!((digamma(1.0) + gamma).abs() < 1e-15);
+///     assert!((digamma(0.5) + gamma + 2.0 * 2f64.ln()).abs() < 1e-15);
+///     assert!((digamma(1e6) - 13.81551005796419).abs() < 1e-14);
+///
+/// Panics unless z > 0.
+pub fn digamma(z: f64) -> f64 {
+    polygamma(0, z)
+}
+
+/// Calculates m! / xᵐ⁺¹ for x > 0 even when m! or xᵐ⁺¹ is out of the range
+/// of `f64`, by multiplying the factors j / x one at a time while keeping
+/// the binary exponent separately.
+fn factorial_over_power(m: u32, x: f64) -> f64 {
+    const LARGE: f64 = 3.273390607896142e150; // 2⁵⁰⁰
+    let mut mantissa = 1.0 / x;
+    let mut exponent = 0i32;
+    for j in 1..m + 1 {
+        mantissa *= f64::from(j) / x;
+        if mantissa > LARGE {
+            mantissa /= LARGE;
+            exponent = exponent.saturating_add(500);
+        } else if mantissa < 1.0 / LARGE {
+            mantissa *= LARGE;
+            exponent = exponent.saturating_sub(500);
+        }
+    }
+    mantissa * 2f64.powi(exponent / 2) * 2f64.powi(exponent - exponent / 2)
+}
+
+/// Calculates the polygamma function ψ⁽ᵐ⁾(z), the m-th derivative of the
+/// digamma function, for z > 0.
+///
+/// Arguments below m + 10 are first shifted upward with the recurrence
+/// ψ⁽ᵐ⁾(z) = ψ⁽ᵐ⁾(z + 1) − (−1)ᵐ m! / zᵐ⁺¹.  The asymptotic expansion (see
+/// `PolygammaCoefficients`) is then summed until the terms drop below the
+/// precision of `f64` or stop decreasing.  Both steps are carried out
+/// relative to m! / zᵐ⁺¹, which is evaluated in pieces if m! or zᵐ⁺¹ is out
+/// of range, so large m only gives ±∞ or 0 if the result itself does.
+///
+///     use bernoulli_numbers::polygamma;
+///
+///     // ψ′(1) = π² / 6 and ψ″(1) = −2 ζ(3)
+///     let pi = std::f64::consts::PI;
+///     assert!((polygamma(1, 1.0) - pi * pi / 6.0).abs() < 1e-15);
+///     assert!((polygamma(2, 1.0) + 2.0 * 1.2020569031595942).abs() < 1e-15);
+///     assert!((polygamma(3, 0.5) - pi.powi(4)).abs() < 1e-12);
+///
+///     // 171! and 1000²⁰¹ are out of range, but the results are not
+///     let x = polygamma(171, 1000.0);
+///     assert!((x / 7.895703658426907e-207 - 1.0).abs() < 1e-13);
+///     let x = polygamma(200, 1000.0);
+///     assert!((x / -4.350819270597102e-228 - 1.0).abs() < 1e-13);
+///     // −200! ζ(201) ≈ −7.9 · 10³⁷⁴
+///     assert_eq!(polygamma(200, 1.0), -std::f64::INFINITY);
+///
+/// Panics unless z > 0.
+pub fn polygamma(m: u32, z: f64) -> f64 {
+    assert!(z > 0.0, "z must be positive");
+    let sign = if m % 2 == 1 { 1.0 } else { -1.0 };
+    let float_factorial: f64 = (1..m + 1).map(f64::from).product();
+    let scale = |x: f64| {
+        let power = x.powi(m as i32 + 1);
+        if float_factorial.is_finite() && power.is_normal() {
+            float_factorial / power
+        } else {
+            factorial_over_power(m, x)
+        }
+    };
+    let mut z = z;
+    let mut shift = 0.0;
+    while z < f64::from(m) + 10.0 {
+        shift += sign * scale(z);
+        z += 1.0;
+    }
+    // The terms of the expansion are measured in units of m! / zᵐ⁺¹, so the
+    // k-th one is B₂ₖ (2k + m − 1)! / ((2k)! m! z²ᵏ⁻¹).
+    let scale = sign * scale(z);
+    let mut sum = if m == 0 {
+        z.ln() - 0.5 / z
+    } else {
+        scale * (z / f64::from(m) + 0.5)
+    };
+    let m_factorial = Rational::from(factorial(Integer::from(m)));
+    let z2 = z * z;
+    let mut power = scale / z;
+    let mut last = f64::INFINITY;
+    // With z ≥ m + 10, each term is roughly (2k + m)² / (2πz)² times the
+    // last, so far fewer terms than this are ever needed.
+    for c in PolygammaCoefficients::new(m).take(100) {
+        let term = rational_to_f64(&(c / &m_factorial)) * power;
+        if !term.is_finite() || term.abs() >= last
+            || term.abs() <= f64::EPSILON / 4.0 * sum.abs() {
+            break;
+        }
+        sum += term;
+        last = term.abs();
+        power /= z2;
+    }
+    sum + shift
+}
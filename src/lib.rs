@@ -1,71 +1,678 @@
+#[cfg(feature = "gmp")]
 extern crate gmp;
+#[cfg(feature = "cache")]
+extern crate fs2;
+#[cfg(feature = "cache")]
+extern crate memmap2;
 extern crate num;
+#[cfg(feature = "bigint")]
+extern crate num_bigint;
+#[cfg(feature = "bigint")]
+extern crate num_rational;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "rug")]
+extern crate rug;
 
-use std::ops::{AddAssign, SubAssign, MulAssign};
-use gmp::mpq::Mpq;
-use gmp::mpz::Mpz;
-use num::{Zero, One};
+#[cfg(not(any(feature = "gmp", feature = "bigint")))]
+compile_error!("either the `gmp` or the `bigint` feature must be enabled");
+
+use std::ops::{AddAssign, SubAssign, MulAssign, Mul, Neg, Sub};
+use std::sync::{Mutex, RwLock};
+use num::{CheckedAdd, Zero, One};
+
+mod backend;
+#[cfg(feature = "cache")]
+mod cache;
+mod character;
+pub mod euler_maclaurin;
+mod faulhaber;
+mod float;
+mod gamma;
+mod modular;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod polynomial;
+mod zeta;
+
+pub use backend::{Integer, Rational};
+#[cfg(feature = "cache")]
+pub use cache::BernoulliCache;
+pub use character::{DirichletCharacter, generalized_bernoulli};
+pub use faulhaber::{FaulhaberPolynomial, power_sum};
+pub use float::{bernoulli_f32, bernoulli_f64, bernoulli_frexp, bernoulli_log2_abs,
+                bernoulli_numerator_bits};
+#[cfg(feature = "rug")]
+pub use float::bernoulli_float;
+pub use gamma::{PolygammaCoefficients, StirlingCoefficients, digamma, lgamma_stirling,
+                polygamma};
+#[cfg(feature = "rug")]
+pub use gamma::lgamma_stirling_float;
+pub use modular::{bernoulli_mod_p, bernoulli_multimodular, bernoulli_n_mod_p,
+                  irregular_pairs};
+#[cfg(feature = "rayon")]
+pub use parallel::bernoulli_table;
+pub use polynomial::BernoulliPolynomial;
+pub use zeta::{dirichlet_beta_odd, dirichlet_beta_odd_f64, zeta_even, zeta_negative};
+
+/// Returns the number of bits needed to represent `n`.
+fn bit_length(n: u64) -> usize {
+    (64 - n.leading_zeros()) as usize
+}
+
+/// Converts a rational number to `f64` approximately, without overflowing
+/// when the numerator or denominator is too large for `f64` on its own.
+fn rational_to_f64(q: &Rational) -> f64 {
+    let num = backend::numer(q);
+    let den = backend::denom(q);
+    if num.is_zero() {
+        return 0.0;
+    }
+    let shift = backend::bit_length(&num) as i64
+        - backend::bit_length(&den) as i64 - 64;
+    let mantissa = if shift < 0 {
+        (num << (-shift) as usize) / den
+    } else {
+        num / (den << shift as usize)
+    };
+    let shift = shift.clamp(-4000, 4000) as i32;
+    backend::to_f64(&mantissa) * 2f64.powi(shift / 2) * 2f64.powi(shift - shift / 2)
+}
+
+/// Primality test by trial division.
+fn is_prime(n: u64) -> bool {
+    if n < 4 {
+        return n >= 2;
+    }
+    if n % 2 == 0 {
+        return false;
+    }
+    let mut d = 3;
+    while d <= n / d {
+        if n % d == 0 {
+            return false;
+        }
+        d += 2;
+    }
+    true
+}
+
+/// Estimates ln(n!) for `n > 0` using Stirling’s formula.
+fn ln_factorial_estimate(n: u64) -> f64 {
+    use std::f64::consts::PI;
+    let n = n as f64;
+    (n + 0.5) * n.ln() - n + 0.5 * (2.0 * PI).ln() + 1.0 / (12.0 * n)
+}
+
+/// Estimates log₂ |Bₙ| for even `n > 0` using Stirling’s formula.  The
+/// result is accurate to well within one unit.
+fn bernoulli_log2_abs_estimate(n: u64) -> f64 {
+    use std::f64::consts::{LN_2, PI};
+    (LN_2 + ln_factorial_estimate(n) - n as f64 * (2.0 * PI).ln()) / LN_2
+}
+
+/// Estimates log₂ |Eₙ| for even `n > 0` using Stirling’s formula.  The
+/// result is accurate to well within one unit.
+fn euler_log2_abs_estimate(n: u64) -> f64 {
+    use std::f64::consts::{LN_2, PI};
+    (n + 2) as f64 + (ln_factorial_estimate(n) - (n + 1) as f64 * PI.ln()) / LN_2
+}
+
+/// Calculates atan(1 / x) · 2^`bits` approximately.
+fn atan_inv_fixed(x: u64, bits: usize) -> Integer {
+    let x2 = Integer::from(x * x);
+    let mut term = (Integer::one() << bits) / Integer::from(x);
+    let mut sum = Integer::zero();
+    let mut k = 1;
+    while !term.is_zero() {
+        if k % 4 == 1 {
+            sum += &term / Integer::from(k);
+        } else {
+            sum -= &term / Integer::from(k);
+        }
+        term /= &x2;
+        k += 2;
+    }
+    sum
+}
+
+/// Calculates π · 2^`bits` approximately, using Machin’s formula.
+fn pi_fixed(bits: usize) -> Integer {
+    atan_inv_fixed(5, bits) * Integer::from(16) - atan_inv_fixed(239, bits) * Integer::from(4)
+}
+
+/// Calculates ζ(s) · 2^`bits` approximately for `s ≥ 2` by direct summation.
+/// The number of terms is only reasonable when `bits / s` is small.
+fn zeta_fixed(s: u64, bits: usize) -> Integer {
+    let one = Integer::one() << bits;
+    let terms = (bits as f64 / (s - 1) as f64).exp2().ceil() as u64 + 1;
+    let mut sum = one.clone();
+    for k in 2..terms + 1 {
+        sum += &one / Integer::from(k).pow(s as u32);
+    }
+    sum
+}
+
+/// Calculates β(s) · 2^`bits` approximately for `s ≥ 1`, where β is the
+/// Dirichlet beta function.  The number of terms is only reasonable when
+/// `bits / s` is small.
+fn beta_fixed(s: u64, bits: usize) -> Integer {
+    let one = Integer::one() << bits;
+    let terms = (bits as f64 / s as f64).exp2().ceil() as u64 / 2 + 1;
+    let mut sum = one.clone();
+    for j in 1..terms + 1 {
+        let term = &one / Integer::from(2 * j + 1).pow(s as u32);
+        if j % 2 == 1 {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+    }
+    sum
+}
+
+/// Calculates xⁿ · 2^`bits` approximately, where `x` is given as x · 2^`bits`.
+fn pow_fixed(x: &Integer, mut n: u64, bits: usize) -> Integer {
+    let mut base = x.clone();
+    let mut result = Integer::one() << bits;
+    while n > 0 {
+        if n % 2 == 1 {
+            result = (result * &base) >> bits;
+        }
+        n /= 2;
+        if n > 0 {
+            base = (&base * &base) >> bits;
+        }
+    }
+    result
+}
+
+/// Calculates atanh(1 / x) · 2^`bits` approximately.
+fn atanh_inv_fixed(x: u64, bits: usize) -> Integer {
+    let x2 = Integer::from(x * x);
+    let mut term = (Integer::one() << bits) / Integer::from(x);
+    let mut sum = Integer::zero();
+    let mut k = 1;
+    while !term.is_zero() {
+        sum += &term / Integer::from(k);
+        term /= &x2;
+        k += 2;
+    }
+    sum
+}
+
+/// Calculates ln(2) · 2^`bits` approximately.
+fn ln2_fixed(bits: usize) -> Integer {
+    atanh_inv_fixed(3, bits) << 1
+}
+
+/// Calculates ln(x) · 2^`bits` approximately, where `x > 0` is given as
+/// x · 2^`bits`.
+fn ln_fixed(x: &Integer, bits: usize) -> Integer {
+    // ln(x) = e ln(2) + 2 atanh((y − 1) / (y + 1)), where y = x / 2ᵉ ∈ [1, 2)
+    let e = backend::bit_length(x) as i64 - 1 - bits as i64;
+    let y = if e >= 0 { x >> e as usize } else { x << (-e) as usize };
+    let one = Integer::one() << bits;
+    let t = ((&y - &one) << bits) / (&y + &one);
+    let t2 = (&t * &t) >> bits;
+    let mut term = t;
+    let mut sum = Integer::zero();
+    let mut k = 1;
+    while !term.is_zero() {
+        sum += &term / Integer::from(k);
+        term = (term * &t2) >> bits;
+        k += 2;
+    }
+    Integer::from(e) * ln2_fixed(bits) + (sum << 1)
+}
+
+/// Calculates exp(x) · 2^`bits` approximately, where 0 ≤ x < 1 is given as
+/// x · 2^`bits`.
+fn exp_fixed(x: &Integer, bits: usize) -> Integer {
+    let mut term = Integer::one() << bits;
+    let mut sum = term.clone();
+    let mut k = 1;
+    while !term.is_zero() {
+        term = ((term * x) >> bits) / Integer::from(k);
+        sum += &term;
+        k += 1;
+    }
+    sum
+}
+
+/// Calculates ln(n!) · 2^`bits` approximately using Stirling’s series,
+/// shifting the argument up first if it is too small for the series to
+/// reach the requested precision.
+fn ln_factorial_fixed(n: u64, bits: usize) -> Integer {
+    // The terms of the series decrease until about the (π m)-th, at which
+    // point they have shrunk to about e^(−2π m).
+    let m = n.max(bits as u64 / 4 + 8);
+    let one = Integer::one() << bits;
+    let ln_m = ln_fixed(&(Integer::from(m) << bits), bits);
+    let ln_2pi = ln_fixed(&(pi_fixed(bits) << 1), bits);
+    // ln(m!) = (m + ½) ln(m) − m + ½ ln(2π) + Σₖ B₂ₖ / (2k (2k − 1) m²ᵏ⁻¹)
+    let mut sum: Integer = ((ln_m * Integer::from(2 * m + 1) + ln_2pi) >> 1)
+        - Integer::from(m) * &one;
+    let m2 = Integer::from(m) * Integer::from(m);
+    let mut power = Integer::from(m);
+    let mut prev: Option<Integer> = None;
+    for k in 1.. {
+        let b = Bernoulli::get(2 * k);
+        let term = (backend::numer(&b) << bits)
+            / (backend::denom(&b) * Integer::from(2 * k * (2 * k - 1)) * &power);
+        let size = if term < Integer::zero() { -term.clone() } else { term.clone() };
+        if size.is_zero() || prev.as_ref().is_some_and(|prev| size > *prev) {
+            break;
+        }
+        sum += term;
+        prev = Some(size);
+        power *= &m2;
+    }
+    if m > n {
+        let mut product = Integer::one();
+        for i in n + 1..m + 1 {
+            product *= Integer::from(i);
+        }
+        sum -= ln_fixed(&(product << bits), bits);
+    }
+    sum
+}
+
+/// Rational number types that `EvenBernoulli` can produce.
+///
+/// Implementations are provided for the backend’s `Rational` type, for
+/// `num_rational::Ratio<T>` (including `BigRational` and primitive ratios
+/// like `Ratio<i128>`) with the `bigint` feature, and for `rug::Rational`
+/// with the `rug` feature.  Note that primitive integers overflow quickly.
+pub trait RationalNumber {
+    /// Integer type used for the intermediate calculations.
+    type Integer: Clone
+        + From<u32>
+        + AddAssign
+        + MulAssign
+        + Mul<Output = Self::Integer>
+        + Sub<Output = Self::Integer>
+        + Neg<Output = Self::Integer>;
+
+    /// Constructs the rational number `numer / denom`.  The fraction is not
+    /// necessarily in lowest terms.
+    fn from_ratio(numer: Self::Integer, denom: Self::Integer) -> Self;
+}
+
+#[cfg(feature = "gmp")]
+impl RationalNumber for gmp::mpq::Mpq {
+    type Integer = gmp::mpz::Mpz;
+    fn from_ratio(numer: Self::Integer, denom: Self::Integer) -> Self {
+        backend::ratio(&numer, &denom)
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl<T> RationalNumber for num_rational::Ratio<T>
+    where T: Clone + num::Integer + From<u32> + AddAssign + MulAssign + Neg<Output = T>
+{
+    type Integer = T;
+    fn from_ratio(numer: T, denom: T) -> Self {
+        num_rational::Ratio::new(numer, denom)
+    }
+}
+
+#[cfg(feature = "rug")]
+impl RationalNumber for rug::Rational {
+    type Integer = rug::Integer;
+    fn from_ratio(numer: rug::Integer, denom: rug::Integer) -> Self {
+        rug::Rational::from((numer, denom))
+    }
+}
 
 /// The even-index Bernoulli numbers ([A000367](https://oeis.org/A000367) /
 /// [A002445](https://oeis.org/A002445)).
 ///
 /// Note: This is an infinite iterator.
 ///
+///     use bernoulli_numbers::EvenBernoulli;
+///
+///     let seq: Vec<_> = EvenBernoulli::default().take(8)
+///         .map(|b| b.to_string()).collect();
+///     assert_eq!(seq, ["1", "1/6", "-1/30", "1/42", "-1/30", "5/66",
+///                      "-691/2730", "7/6"]);
+///
+/// `EvenBernoulli::default()` produces the backend’s `Rational` type.  Use
+/// `EvenBernoulli::new()` to produce any other `RationalNumber`:
+///
 ///     # extern crate bernoulli_numbers;
-///     extern crate gmp;
+///     # #[cfg(feature = "bigint")]
+///     extern crate num_rational;
 ///
+///     # #[cfg(feature = "bigint")]
+///     # fn main() {
 ///     use bernoulli_numbers::EvenBernoulli;
-///     use gmp::mpq::Mpq;
+///     use num_rational::Ratio;
 ///
-///     # fn main() {
-///     let seq: Vec<_> = EvenBernoulli::default().take(8).collect();
-///     assert_eq!(seq, [Mpq::from(1),
-///                      Mpq::from(1) / Mpq::from(6),
-///                      Mpq::from(-1) / Mpq::from(30),
-///                      Mpq::from(1) / Mpq::from(42),
-///                      Mpq::from(-1) / Mpq::from(30),
-///                      Mpq::from(5) / Mpq::from(66),
-///                      Mpq::from(-691) / Mpq::from(2730),
-///                      Mpq::from(7) / Mpq::from(6)]);
+///     let seq: Vec<Ratio<i128>> = EvenBernoulli::new().take(4).collect();
+///     assert_eq!(seq, [Ratio::from_integer(1),
+///                      Ratio::new(1, 6),
+///                      Ratio::new(-1, 30),
+///                      Ratio::new(1, 42)]);
 ///     # }
+///     # #[cfg(not(feature = "bigint"))]
+///     # fn main() {}
 ///
-pub struct EvenBernoulli {
+pub struct EvenBernoulli<Q: RationalNumber = Rational> {
     i: i64,
-    power: Mpz,
-    zs: EulerUpDown<Mpz>,
+    power: Q::Integer,
+    ts: TangentNumbers<Q::Integer>,
 }
 
 impl Default for EvenBernoulli {
     fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Q: RationalNumber> EvenBernoulli<Q> {
+    /// Creates the sequence for an arbitrary rational number type.
+    pub fn new() -> Self {
         Self {
             i: Default::default(),
-            power: One::one(),
-            zs: Default::default(),
+            power: Q::Integer::from(1),
+            ts: Default::default(),
         }
     }
 }
 
-impl Iterator for EvenBernoulli {
-    type Item = Mpq;
+impl<Q: RationalNumber> Iterator for EvenBernoulli<Q> {
+    type Item = Q;
     fn next(&mut self) -> Option<Self::Item> {
         let i = self.i;
         self.i = -(i + if i >= 0 { 2 } else { -2 });
         Some(if i == 0 {
-            One::one()
+            Q::from_ratio(Q::Integer::from(1), Q::Integer::from(1))
         } else {
-            let z = match self.zs.nth(1) {
-                None => return None,
-                Some(z) => z,
+            let z = self.ts.next()?;
+            self.power *= Q::Integer::from(4);
+            let a = self.power.clone();
+            let b = a.clone() * a.clone();
+            let i = if i < 0 {
+                -Q::Integer::from(-i as u32)
+            } else {
+                Q::Integer::from(i as u32)
             };
-            self.power *= Mpz::from(4);
-            let a = &self.power;
-            let b = a.pow(2);
-            let i = Mpz::from(i);
-            Mpq::from(i * z) / Mpq::from(a - b)
+            Q::from_ratio(i * z, a - b)
         })
     }
 }
 
+/// Sign convention for B₁.
+///
+/// The generating function t / (eᵗ − 1) gives B₁ = −½, whereas
+/// t / (1 − e⁻ᵗ) gives B₁ = +½.  All other Bernoulli numbers agree.
+///
+///     use bernoulli_numbers::B1Convention;
+///
+///     assert_eq!(B1Convention::default(), B1Convention::Minus);
+///     assert_eq!(B1Convention::Minus.b1().to_string(), "-1/2");
+///     assert_eq!(B1Convention::Plus.b1().to_string(), "1/2");
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum B1Convention {
+    /// B₁ = −½
+    #[default]
+    Minus,
+    /// B₁ = +½
+    Plus,
+}
+
+impl B1Convention {
+    /// Value of B₁ under this convention.
+    pub fn b1(self) -> Rational {
+        match self {
+            B1Convention::Minus => backend::ratio(&Integer::from(-1), &Integer::from(2)),
+            B1Convention::Plus => backend::ratio(&Integer::from(1), &Integer::from(2)),
+        }
+    }
+}
+
+/// The Bernoulli numbers ([A027641](https://oeis.org/A027641) /
+/// [A027642](https://oeis.org/A027642)), including B₁ and the odd-index
+/// zeros.
+///
+/// Note: This is an infinite iterator.
+///
+///     use bernoulli_numbers::{B1Convention, Bernoulli};
+///
+///     let seq: Vec<_> = Bernoulli::default().take(7)
+///         .map(|b| b.to_string()).collect();
+///     assert_eq!(seq, ["1", "-1/2", "1/6", "0", "-1/30", "0", "1/42"]);
+///
+///     let b1 = Bernoulli::new(B1Convention::Plus).nth(1).unwrap();
+///     assert_eq!(b1.to_string(), "1/2");
+///
+pub struct Bernoulli {
+    i: u64,
+    convention: B1Convention,
+    evens: EvenBernoulli,
+}
+
+impl Default for Bernoulli {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl Bernoulli {
+    /// Creates the sequence with the given sign convention for B₁.
+    pub fn new(convention: B1Convention) -> Self {
+        Self {
+            i: 0,
+            convention,
+            evens: Default::default(),
+        }
+    }
+
+    /// Looks up Bₙ, using the convention B₁ = −½, in a table shared by the
+    /// whole process.
+    ///
+    /// The table grows on demand.  Only one thread extends it at a time,
+    /// while other threads can still read the entries that already exist;
+    /// threads that need the same new entries wait for them instead of
+    /// computing them again.
+    ///
+    ///     use std::thread;
+    ///     use bernoulli_numbers::{Bernoulli, bernoulli};
+    ///
+    ///     let threads: Vec<_> = (0..4).map(|i| {
+    ///         thread::spawn(move || Bernoulli::get(100 + 2 * i).to_string())
+    ///     }).collect();
+    ///     for (i, t) in threads.into_iter().enumerate() {
+    ///         assert_eq!(t.join().unwrap(), bernoulli(100 + 2 * i as u64).to_string());
+    ///     }
+    ///     assert_eq!(Bernoulli::get(12).to_string(), "-691/2730");
+    ///
+    pub fn get(n: u64) -> Rational {
+        if n == 1 {
+            return B1Convention::Minus.b1();
+        }
+        if n % 2 == 1 {
+            return Rational::zero();
+        }
+        let k = (n / 2) as usize;
+        if let Some(b) = SHARED_TABLE.read().unwrap().get(k) {
+            return b.0.clone();
+        }
+        // Holding this lock entitles the thread to extend the table, so the
+        // generator has always produced exactly the entries in the table.
+        let mut evens = SHARED_EVENS.lock().unwrap();
+        let evens = evens.get_or_insert_with(Default::default);
+        loop {
+            if let Some(b) = SHARED_TABLE.read().unwrap().get(k) {
+                return b.0.clone();
+            }
+            let b = evens.next().unwrap();
+            SHARED_TABLE.write().unwrap().push(SharedEntry(b));
+        }
+    }
+}
+
+/// Even-index Bernoulli numbers computed so far by `Bernoulli::get`.
+static SHARED_TABLE: RwLock<Vec<SharedEntry>> = RwLock::new(Vec::new());
+
+/// An entry of `SHARED_TABLE`.  The GMP types are `Send` but not `Sync`,
+/// since GMP leaves synchronization to the caller.
+struct SharedEntry(Rational);
+
+// SAFETY: Entries are never modified after being added to the table, and
+// are only accessed to clone them.  GMP only reads from its source operands,
+// which is safe to do from several threads at once.
+unsafe impl Sync for SharedEntry {}
+
+/// Generator for the entries of `SHARED_TABLE`.
+static SHARED_EVENS: Mutex<Option<EvenBernoulli>> = Mutex::new(None);
+
+impl Iterator for Bernoulli {
+    type Item = Rational;
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.i;
+        self.i += 1;
+        if i == 1 {
+            Some(self.convention.b1())
+        } else if i % 2 == 1 {
+            Some(Rational::zero())
+        } else {
+            self.evens.next()
+        }
+    }
+}
+
+/// Indices below this are cheaper to reach by iterating `EvenBernoulli`.
+const DIRECT_BERNOULLI_THRESHOLD: u64 = 32;
+
+/// Calculates a single Bernoulli number, using the convention B₁ = −½.  See
+/// `bernoulli_with_convention` for the other convention.
+///
+/// Unlike `EvenBernoulli`, this does not need to compute any of the earlier
+/// terms.  For large `n`, the numerator is recovered by evaluating
+/// |Bₙ| = 2 n! ζ(n) / (2π)ⁿ in fixed-point arithmetic to just enough bits,
+/// while the denominator is obtained from the von Staudt–Clausen theorem.
+///
+///     use bernoulli_numbers::{EvenBernoulli, bernoulli};
+///
+///     assert_eq!(bernoulli(0).to_string(), "1");
+///     assert_eq!(bernoulli(1).to_string(), "-1/2");
+///     assert_eq!(bernoulli(3).to_string(), "0");
+///     assert_eq!(bernoulli(12).to_string(), "-691/2730");
+///     let seq: Vec<_> = EvenBernoulli::default().take(60).collect();
+///     for (i, b) in seq.into_iter().enumerate() {
+///         assert_eq!(bernoulli(2 * i as u64), b);
+///     }
+///
+pub fn bernoulli(n: u64) -> Rational {
+    bernoulli_with_convention(n, B1Convention::Minus)
+}
+
+/// Calculates a single Bernoulli number, using the given sign convention for
+/// B₁.
+///
+///     use bernoulli_numbers::{B1Convention, bernoulli_with_convention};
+///
+///     let b1 = bernoulli_with_convention(1, B1Convention::Plus);
+///     assert_eq!(b1.to_string(), "1/2");
+///     let b2 = bernoulli_with_convention(2, B1Convention::Plus);
+///     assert_eq!(b2.to_string(), "1/6");
+///
+pub fn bernoulli_with_convention(n: u64, convention: B1Convention) -> Rational {
+    if n == 1 {
+        return convention.b1();
+    }
+    if n % 2 == 1 {
+        return Rational::zero();
+    }
+    if n < DIRECT_BERNOULLI_THRESHOLD {
+        return EvenBernoulli::default().nth((n / 2) as usize).unwrap();
+    }
+    let denom = bernoulli_denominator(n);
+    backend::ratio(&numerator_from_zeta(n, &denom), &denom)
+}
+
+/// Calculates the numerator of Bₙ for even `n > 0`, given its denominator,
+/// by evaluating |Bₙ| = 2 n! ζ(n) / (2π)ⁿ in fixed-point arithmetic.
+fn numerator_from_zeta(n: u64, denom: &Integer) -> Integer {
+    let numer_bits = bernoulli_log2_abs_estimate(n).ceil() as usize
+        + backend::bit_length(denom) + 2;
+    let bits = numer_bits + 2 * bit_length(n) + 32;
+    let two_pi = pi_fixed(bits) << 1;
+    let num = (factorial(Integer::from(n)) * denom * zeta_fixed(n, bits)) << 2;
+    let den = pow_fixed(&two_pi, n, bits);
+    let numer: Integer = (num / den + Integer::one()) >> 1;
+    if n % 4 == 0 { -numer } else { numer }
+}
+
+/// Calculates the numerator of Bₙ in lowest terms, using the convention
+/// B₁ = −½.  The sign of Bₙ is carried by the numerator.
+///
+/// For large `n`, this skips the normalization of the rational number that
+/// `bernoulli` performs, since the denominator is already known.
+///
+///     use bernoulli_numbers::{bernoulli, bernoulli_numerator};
+///
+///     assert_eq!(bernoulli_numerator(1).to_string(), "-1");
+///     assert_eq!(bernoulli_numerator(3).to_string(), "0");
+///     assert_eq!(bernoulli_numerator(12).to_string(), "-691");
+///     assert_eq!(bernoulli_numerator(40).to_string(), "-261082718496449122051");
+///
+pub fn bernoulli_numerator(n: u64) -> Integer {
+    if n % 2 == 1 || n < DIRECT_BERNOULLI_THRESHOLD {
+        return backend::numer(&bernoulli(n));
+    }
+    numerator_from_zeta(n, &bernoulli_denominator(n))
+}
+
+/// Calculates the denominator of Bₙ in lowest terms without calculating Bₙ
+/// itself.  For even `n > 0`, it is the product of all primes p such that
+/// (p − 1) divides `n`, according to the von Staudt–Clausen theorem.
+///
+///     use bernoulli_numbers::bernoulli_denominator;
+///
+///     assert_eq!(bernoulli_denominator(0).to_string(), "1");
+///     assert_eq!(bernoulli_denominator(1).to_string(), "2");
+///     assert_eq!(bernoulli_denominator(3).to_string(), "1");
+///     assert_eq!(bernoulli_denominator(12).to_string(), "2730");
+///     assert_eq!(bernoulli_denominator(1000).to_string(), "342999030");
+///
+pub fn bernoulli_denominator(n: u64) -> Integer {
+    let mut denom: Integer = One::one();
+    if n == 1 {
+        denom = Integer::from(2);
+    }
+    if n == 0 || n % 2 == 1 {
+        return denom;
+    }
+    for p in staudt_clausen_primes(n) {
+        denom *= Integer::from(p);
+    }
+    denom
+}
+
+/// Finds the primes p such that (p − 1) divides `n`, which are the prime
+/// factors of the denominator of Bₙ for even `n > 0`.
+fn staudt_clausen_primes(n: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    let mut d = 1;
+    while d <= n / d {
+        if n % d == 0 {
+            if is_prime(d + 1) {
+                primes.push(d + 1);
+            }
+            let e = n / d;
+            if e != d && is_prime(e + 1) {
+                primes.push(e + 1);
+            }
+        }
+        d += 1;
+    }
+    primes
+}
+
 /// Euler up/down (“zigzag”) numbers ([A000111](https://oeis.org/A000111)).
 ///
 /// Note: This is an infinite iterator.
@@ -76,25 +683,40 @@ impl Iterator for EvenBernoulli {
 ///     assert_eq!(seq, [1, 1, 1, 2, 5, 16, 61, 272]);
 ///
 pub struct EulerUpDown<T> {
+    one: T,
     source: Vec<T>,
     sink: Vec<T>,
 }
 
-impl<T> Default for EulerUpDown<T> {
+impl<T: One> Default for EulerUpDown<T> {
     fn default() -> Self {
+        Self::with_one(One::one())
+    }
+}
+
+impl<T> EulerUpDown<T> {
+    /// Creates the sequence using the given value of one.  This allows the
+    /// use of types that carry state at runtime, such as integers modulo a
+    /// number that is not known at compile time.
+    pub fn with_one(one: T) -> Self {
         Self {
+            one,
             source: Default::default(),
             sink: Default::default(),
         }
     }
 }
 
-impl<T: Clone + One + AddAssign> Iterator for EulerUpDown<T> {
-    type Item = T;
-    fn next(&mut self) -> Option<Self::Item> {
+impl<T: Clone> EulerUpDown<T> {
+    /// Computes the next row of the Seidel triangle, adding entries with
+    /// `add`, and returns the next item along with whether the row was
+    /// completed.  If `add` fails, the triangle is cleared instead.
+    fn step<F>(&mut self, mut add: F) -> (T, bool)
+        where F: FnMut(T, T) -> Option<T>
+    {
         std::mem::swap(&mut self.source, &mut self.sink);
         let mut accum = match self.source.pop() {
-            None => One::one(),
+            None => self.one.clone(),
             Some(accum) => {
                 self.sink.push(accum.clone());
                 accum
@@ -102,14 +724,228 @@ impl<T: Clone + One + AddAssign> Iterator for EulerUpDown<T> {
         };
         let item = accum.clone();
         while let Some(i) = self.source.pop() {
-            accum += i;
+            accum = match add(accum, i) {
+                Some(accum) => accum,
+                None => {
+                    self.source.clear();
+                    self.sink.clear();
+                    return (item, false);
+                }
+            };
             self.sink.push(accum.clone());
         }
         self.sink.push(accum);
+        (item, true)
+    }
+}
+
+impl<T: Clone + AddAssign> Iterator for EulerUpDown<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (item, _) = self.step(|mut accum, i| {
+            accum += i;
+            Some(accum)
+        });
         Some(item)
     }
 }
 
+impl<T: One> EulerUpDown<T> {
+    /// Creates a variant of the sequence that detects overflow, for use with
+    /// fixed-width integers.
+    ///
+    /// Once an entry no longer fits in `T`, the iterator yields
+    /// `Err(Overflow { index })` and then ends.
+    ///
+    ///     use bernoulli_numbers::{EulerUpDown, Overflow};
+    ///
+    ///     let seq: Vec<_> = EulerUpDown::<u8>::checked().collect();
+    ///     assert_eq!(seq, [Ok(1), Ok(1), Ok(1), Ok(2), Ok(5), Ok(16), Ok(61),
+    ///                      Err(Overflow { index: 7 })]);
+    ///
+    ///     let n = EulerUpDown::<u64>::checked().take_while(Result::is_ok).count();
+    ///     assert_eq!(n, 25);
+    ///
+    pub fn checked() -> CheckedEulerUpDown<T> {
+        CheckedEulerUpDown {
+            index: 0,
+            overflow: None,
+            zs: Default::default(),
+        }
+    }
+}
+
+/// Error indicating that the entry at `index` of a sequence does not fit in
+/// the integer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Overflow {
+    pub index: usize,
+}
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "overflow at index {}", self.index)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Euler up/down (“zigzag”) numbers with overflow detection.  See
+/// `EulerUpDown::checked`.
+pub struct CheckedEulerUpDown<T> {
+    index: usize,
+    overflow: Option<usize>,
+    zs: EulerUpDown<T>,
+}
+
+impl<T: Clone + CheckedAdd> Iterator for CheckedEulerUpDown<T> {
+    type Item = Result<T, Overflow>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.index;
+        if let Some(overflow) = self.overflow {
+            if overflow != index {
+                return None;
+            }
+            self.index += 1;
+            return Some(Err(Overflow { index }));
+        }
+        let (item, completed) = self.zs.step(|accum, i| accum.checked_add(&i));
+        self.index += 1;
+        if !completed {
+            // the last entry of the row is the largest and equals the next
+            // item, so any overflow in the row means the next item overflows
+            self.overflow = Some(index + 1);
+        }
+        Some(Ok(item))
+    }
+}
+
+/// The Euler (secant) numbers E₀, E₂, E₄, … with signs
+/// ([A122045](https://oeis.org/A122045); the absolute values are
+/// [A000364](https://oeis.org/A000364)).  The odd-index Euler numbers are all
+/// zero.
+///
+/// These are the even-index entries of `EulerUpDown` with alternating signs.
+///
+/// Note: This is an infinite iterator.
+///
+///     use bernoulli_numbers::EulerNumbers;
+///
+///     let seq: Vec<i64> = EulerNumbers::default().take(7).collect();
+///     assert_eq!(seq, [1, -1, 5, -61, 1385, -50521, 2702765]);
+///
+pub struct EulerNumbers<T> {
+    negate: bool,
+    zs: EulerUpDown<T>,
+}
+
+impl<T: One> Default for EulerNumbers<T> {
+    fn default() -> Self {
+        Self {
+            negate: false,
+            zs: Default::default(),
+        }
+    }
+}
+
+impl<T: Clone + AddAssign + Neg<Output = T>> Iterator for EulerNumbers<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let z = self.zs.next()?;
+        // skip over the tangent number
+        self.zs.next();
+        let negate = self.negate;
+        self.negate = !negate;
+        Some(if negate { -z } else { z })
+    }
+}
+
+/// The tangent numbers T₁, T₂, T₃, … ([A000182](https://oeis.org/A000182)),
+/// which are the odd-index entries of `EulerUpDown`.
+///
+/// This uses the in-place algorithm of Brent and Harvey, which needs only
+/// about half as many additions as the Seidel triangle in `EulerUpDown`, at
+/// the cost of an equal number of multiplications by small integers.
+///
+/// Note: This is an infinite iterator.
+///
+///     use bernoulli_numbers::TangentNumbers;
+///
+///     let seq: Vec<u64> = TangentNumbers::default().take(7).collect();
+///     assert_eq!(seq, [1, 2, 16, 272, 7936, 353792, 22368256]);
+///
+pub struct TangentNumbers<T> {
+    column: Vec<T>,
+}
+
+impl<T> Default for TangentNumbers<T> {
+    fn default() -> Self {
+        Self {
+            column: Default::default(),
+        }
+    }
+}
+
+impl<T> Iterator for TangentNumbers<T>
+    where T: Clone + From<u32> + AddAssign + MulAssign
+{
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        // The column holds the j-th entry of each pass of Brent and Harvey’s
+        // algorithm; extending it to j + 1 completes the (j + 1)-th pass.
+        let j = self.column.len() as u32 + 1;
+        let mut prev = T::from(1);
+        for (k, c) in self.column.iter_mut().enumerate() {
+            let k = k as u32 + 1;
+            *c *= T::from(j - k);
+            if k > 1 {
+                prev *= T::from(j - k + 2);
+                *c += prev;
+            }
+            prev = c.clone();
+        }
+        if j > 1 {
+            prev *= T::from(2);
+        }
+        self.column.push(prev.clone());
+        Some(prev)
+    }
+}
+
+/// Indices below this are cheaper to reach by iterating `EulerNumbers`.
+const DIRECT_EULER_THRESHOLD: u64 = 32;
+
+/// Calculates a single Euler number Eₙ.
+///
+/// Like `bernoulli`, this does not compute any of the earlier terms.  For
+/// large `n`, it evaluates |Eₙ| = 2ⁿ⁺² n! β(n + 1) / πⁿ⁺¹ in fixed-point
+/// arithmetic, where β is the Dirichlet beta function.
+///
+///     use bernoulli_numbers::{EulerNumbers, Integer, euler_number};
+///
+///     assert_eq!(euler_number(0), Integer::from(1));
+///     assert_eq!(euler_number(1), Integer::from(0));
+///     assert_eq!(euler_number(10), Integer::from(-50521));
+///     let seq: Vec<Integer> = EulerNumbers::default().take(60).collect();
+///     for (i, e) in seq.into_iter().enumerate() {
+///         assert_eq!(euler_number(2 * i as u64), e);
+///     }
+///
+pub fn euler_number(n: u64) -> Integer {
+    if n % 2 == 1 {
+        return Integer::zero();
+    }
+    if n < DIRECT_EULER_THRESHOLD {
+        return EulerNumbers::default().nth((n / 2) as usize).unwrap();
+    }
+    let bits = euler_log2_abs_estimate(n).ceil() as usize + 2
+        + 2 * bit_length(n) + 32;
+    let num = (factorial(Integer::from(n)) * beta_fixed(n + 1, bits)) << (n as usize + 3);
+    let den = pow_fixed(&pi_fixed(bits), n + 1, bits);
+    let e: Integer = (num / den + Integer::one()) >> 1;
+    if n % 4 == 2 { -e } else { e }
+}
+
 /// Calculates the factorial.
 ///
 ///     use bernoulli_numbers::factorial;
@@ -0,0 +1,113 @@
+use num::{One, Zero};
+use rayon::prelude::*;
+use super::{B1Convention, Integer, Rational, backend};
+
+/// A contiguous range of the column used by `TangentNumbers`, starting at
+/// index `start`.  Only the entries created so far are stored.
+struct Block {
+    start: usize,
+    end: usize,
+    entries: Vec<Integer>,
+}
+
+impl Block {
+    /// Runs the j-th pass of Brent and Harvey’s algorithm over this block,
+    /// given the new value of the entry just before it.  Returns the new
+    /// value of the last entry, along with Tⱼ if it was created here.
+    fn pass(&mut self, j: usize, mut prev: Integer)
+            -> (Integer, Option<Integer>) {
+        for (i, c) in self.entries.iter_mut().enumerate() {
+            let k = self.start + i + 1;
+            *c *= Integer::from((j - k) as u64);
+            if k > 1 {
+                prev *= Integer::from((j - k + 2) as u64);
+                *c += &prev;
+            }
+            prev = c.clone();
+        }
+        let next = self.start + self.entries.len();
+        if next + 1 != j || next == self.end {
+            return (prev, None);
+        }
+        if j > 1 {
+            prev *= Integer::from(2);
+        }
+        self.entries.push(prev.clone());
+        (prev.clone(), Some(prev))
+    }
+}
+
+/// Calculates the tangent numbers T₁, …, Tₘ.
+///
+/// The column is split into blocks, and the passes sweep across them as a
+/// wavefront: in each step, block b runs pass j − b for every b in
+/// parallel, using the value that block b − 1 produced in the previous step.
+fn tangent_numbers(m: usize) -> Vec<Integer> {
+    let num_blocks = (4 * rayon::current_num_threads()).min(m / 16).max(1);
+    let mut blocks: Vec<_> = (0..num_blocks).map(|b| Block {
+        start: b * m / num_blocks,
+        end: (b + 1) * m / num_blocks,
+        entries: Vec::new(),
+    }).collect();
+    let mut inputs: Vec<Option<Integer>> = vec![None; num_blocks];
+    let mut ts = Vec::with_capacity(m);
+    for step in 1..m + num_blocks {
+        inputs[0] = if step <= m { Some(One::one()) } else { None };
+        let outputs: Vec<_> = blocks.par_iter_mut().zip(inputs.par_iter_mut())
+            .enumerate()
+            .map(|(b, (block, input))| {
+                input.take().map(|prev| block.pass(step - b, prev))
+            })
+            .collect();
+        for (b, output) in outputs.into_iter().enumerate() {
+            if let Some((prev, t)) = output {
+                ts.extend(t);
+                if b + 1 < num_blocks {
+                    inputs[b + 1] = Some(prev);
+                }
+            }
+        }
+    }
+    ts
+}
+
+/// Calculates B₀, B₁, …, B_{len − 1} in parallel, using the convention
+/// B₁ = −½.  This requires the `rayon` feature.
+///
+/// The tangent numbers are generated as in `EvenBernoulli`, but with each
+/// pass over the column spread across threads.  The Bernoulli numbers are
+/// then assembled from them independently.
+///
+///     use bernoulli_numbers::{Bernoulli, bernoulli_table};
+///
+///     let table = bernoulli_table(200);
+///     assert_eq!(table[12].to_string(), "-691/2730");
+///     assert_eq!(table, Bernoulli::default().take(200).collect::<Vec<_>>());
+///
+pub fn bernoulli_table(len: usize) -> Vec<Rational> {
+    // The GMP integers are `Send` but not `Sync`, so each thread takes
+    // ownership of the tangent numbers it needs rather than sharing them.
+    let ts = tangent_numbers(len.saturating_sub(1) / 2);
+    let evens: Vec<Rational> = ts.into_par_iter().enumerate().map(|(i, t)| {
+        // B₂ₖ = (−1)ᵏ 2k Tₖ / (4ᵏ − 16ᵏ)
+        let k = i + 1;
+        let n = 2 * k;
+        let a: Integer = Integer::from(1u32) << n;
+        let b: Integer = Integer::from(1u32) << (2 * n);
+        let numer = Integer::from(n as u64) * &t;
+        let numer = if k % 2 == 1 { -numer } else { numer };
+        backend::ratio(&numer, &(a - b))
+    }).collect();
+    let mut evens = evens.into_iter();
+    (0..len).map(|n| {
+        if n == 1 {
+            B1Convention::Minus.b1()
+        } else if n % 2 == 1 {
+            Zero::zero()
+        } else if n == 0 {
+            One::one()
+        } else {
+            evens.next().unwrap()
+        }
+    }).collect()
+}
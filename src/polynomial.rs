@@ -0,0 +1,101 @@
+use num::One;
+use super::{Bernoulli, Integer, Rational, rational_to_f64};
+
+/// A Bernoulli polynomial Bₙ(x), with exact rational coefficients.
+///
+/// The polynomials satisfy Bₙ(0) = Bₙ (with B₁ = −½) and
+/// Bₙ′(x) = n Bₙ₋₁(x).
+///
+///     use bernoulli_numbers::{BernoulliPolynomial, Integer, Rational};
+///
+///     // B₂(x) = x² − x + 1/6
+///     let p = BernoulliPolynomial::new(2);
+///     let coeffs: Vec<_> = p.coefficients().iter()
+///         .map(|c| c.to_string()).collect();
+///     assert_eq!(coeffs, ["1/6", "-1", "1"]);
+///     let half = Rational::from(Integer::from(1)) / Rational::from(Integer::from(2));
+///     assert_eq!(p.eval(&half).to_string(), "-1/12");
+///     assert!((p.eval_f64(0.25) + 0.0208333333333333).abs() < 1e-15);
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BernoulliPolynomial {
+    coeffs: Vec<Rational>,
+}
+
+impl BernoulliPolynomial {
+    /// Constructs Bₙ(x).
+    pub fn new(n: u64) -> Self {
+        let mut coeffs = Vec::with_capacity(n as usize + 1);
+        let mut binomial: Integer = One::one();
+        for (k, b) in Bernoulli::default().take(n as usize + 1).enumerate() {
+            coeffs.push(Rational::from(binomial.clone()) * b);
+            let k = k as u64;
+            binomial = binomial * Integer::from(n - k) / Integer::from(k + 1);
+        }
+        coeffs.reverse();
+        Self { coeffs }
+    }
+
+    /// The degree `n` of the polynomial.
+    pub fn degree(&self) -> u64 {
+        self.coeffs.len() as u64 - 1
+    }
+
+    /// The coefficients in order of increasing powers of `x`.
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coeffs
+    }
+
+    /// Evaluates the polynomial exactly.
+    pub fn eval(&self, x: &Rational) -> Rational {
+        let mut coeffs = self.coeffs.iter().rev();
+        let mut accum = coeffs.next().unwrap().clone();
+        for c in coeffs {
+            accum = &accum * x + c;
+        }
+        accum
+    }
+
+    /// Evaluates the polynomial approximately using floating-point
+    /// arithmetic.  Beware that cancellation makes this inaccurate for large
+    /// degrees; use `eval` if that is a concern.
+    pub fn eval_f64(&self, x: f64) -> f64 {
+        self.coeffs.iter().rev().fold(0.0, |accum, c| accum * x + rational_to_f64(c))
+    }
+
+    /// The coefficients of the derivative Bₙ′(x) = n Bₙ₋₁(x), in order of
+    /// increasing powers of `x`.
+    ///
+    ///     use bernoulli_numbers::{BernoulliPolynomial, Integer, Rational};
+    ///
+    ///     let seven = Rational::from(Integer::from(7));
+    ///     let lower: Vec<_> = BernoulliPolynomial::new(6).coefficients().iter()
+    ///         .map(|c| &seven * c).collect();
+    ///     assert_eq!(BernoulliPolynomial::new(7).derivative(), lower);
+    ///
+    pub fn derivative(&self) -> Vec<Rational> {
+        self.coeffs.iter().enumerate().skip(1)
+            .map(|(k, c)| Rational::from(Integer::from(k as u64)) * c)
+            .collect()
+    }
+
+    /// Calculates the definite integral of Bₙ(x) from `a` to `b`, which is
+    /// (Bₙ₊₁(b) − Bₙ₊₁(a)) / (n + 1).
+    ///
+    ///     use bernoulli_numbers::{BernoulliPolynomial, Integer, Rational};
+    ///
+    ///     let zero = Rational::from(Integer::from(0));
+    ///     // The integral over a unit interval is zero for n ≥ 1.
+    ///     let p = BernoulliPolynomial::new(5);
+    ///     let one = Rational::from(Integer::from(1));
+    ///     assert_eq!(p.integrate(&zero, &one).to_string(), "0");
+    ///     // Over [0, m], the integral of Bₙ is a sum of n-th powers.
+    ///     let p = BernoulliPolynomial::new(2);
+    ///     let four = Rational::from(Integer::from(4));
+    ///     assert_eq!(p.integrate(&zero, &four).to_string(), "14");
+    ///
+    pub fn integrate(&self, a: &Rational, b: &Rational) -> Rational {
+        let upper = BernoulliPolynomial::new(self.degree() + 1);
+        (upper.eval(b) - upper.eval(a)) / Rational::from(Integer::from(self.degree() + 1))
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Bernoulli, Integer, Rational, backend, euler_number, factorial, rational_to_f64};
+
+/// Calculates ζ(2n) as an exact rational multiple of a power of π.
+/// Returns the pair (q, 2n) such that ζ(2n) = q π²ⁿ.
+///
+/// This uses ζ(2n) = (−1)ⁿ⁺¹ B₂ₙ (2π)²ⁿ / (2 (2n)!), which also holds for
+/// ζ(0) = −½.
+///
+///     use bernoulli_numbers::zeta_even;
+///
+///     let (q, p) = zeta_even(1);
+///     assert_eq!((q.to_string(), p), ("1/6".to_string(), 2));
+///     let (q, p) = zeta_even(2);
+///     assert_eq!((q.to_string(), p), ("1/90".to_string(), 4));
+///     let (q, p) = zeta_even(6);
+///     assert_eq!((q.to_string(), p), ("691/638512875".to_string(), 12));
+///     assert_eq!(zeta_even(0).0.to_string(), "-1/2");
+///
+pub fn zeta_even(n: u64) -> (Rational, u64) {
+    if n == 0 {
+        return (backend::ratio(&Integer::from(-1), &Integer::from(2)), 0);
+    }
+    let power: Integer = Integer::from(1u32) << (2 * n - 1) as usize;
+    let q = Bernoulli::get(2 * n) * Rational::from(power)
+        / &Rational::from(factorial(Integer::from(2 * n)));
+    (if n % 2 == 1 { q } else { -q }, 2 * n)
+}
+
+/// Calculates ζ(−n) = (−1)ⁿ Bₙ₊₁ / (n + 1) exactly, using the convention
+/// B₁ = −½.  This is zero for even n > 0.
+///
+///     use bernoulli_numbers::zeta_negative;
+///
+///     let seq: Vec<_> = (0..8).map(|n| zeta_negative(n).to_string()).collect();
+///     assert_eq!(seq, ["-1/2", "-1/12", "0", "1/120", "0", "-1/252", "0",
+///                      "1/240"]);
+///
+pub fn zeta_negative(n: u64) -> Rational {
+    let q = Bernoulli::get(n + 1) / &Rational::from(Integer::from(n + 1));
+    if n % 2 == 1 { -q } else { q }
+}
+
+/// Calculates β(2n + 1), where β is the Dirichlet beta function, as an
+/// exact rational multiple of a power of π.  Returns the pair (q, 2n + 1)
+/// such that β(2n + 1) = q π²ⁿ⁺¹.
+///
+/// This uses β(2n + 1) = (−1)ⁿ E₂ₙ π²ⁿ⁺¹ / (4ⁿ⁺¹ (2n)!), where E₂ₙ is an
+/// Euler number.
+///
+///     use bernoulli_numbers::dirichlet_beta_odd;
+///
+///     let (q, p) = dirichlet_beta_odd(0);
+///     assert_eq!((q.to_string(), p), ("1/4".to_string(), 1));
+///     let (q, p) = dirichlet_beta_odd(1);
+///     assert_eq!((q.to_string(), p), ("1/32".to_string(), 3));
+///     let (q, p) = dirichlet_beta_odd(4);
+///     assert_eq!((q.to_string(), p), ("277/8257536".to_string(), 9));
+///
+pub fn dirichlet_beta_odd(n: u64) -> (Rational, u64) {
+    let power: Integer = Integer::from(1u32) << (2 * n + 2) as usize;
+    let q = backend::ratio(&euler_number(2 * n),
+                           &(factorial(Integer::from(2 * n)) * &power));
+    (if n % 2 == 1 { -q } else { q }, 2 * n + 1)
+}
+
+/// Indices below this are evaluated from the exact coefficient, since the
+/// series for β(2n + 1) converges too slowly.
+const DIRECT_BETA_THRESHOLD: u64 = 6;
+
+/// Calculates β(2n + 1) approximately, where β is the Dirichlet beta
+/// function.
+///
+/// Small arguments are evaluated from `dirichlet_beta_odd`.  Otherwise, the
+/// series β(s) = 1 − 3⁻ˢ + 5⁻ˢ − … converges within a few terms.
+///
+///     use bernoulli_numbers::dirichlet_beta_odd_f64;
+///
+///     let pi = std::f64::consts::PI;
+///     assert!((dirichlet_beta_odd_f64(0) - pi / 4.0).abs() < 1e-16);
+///     assert!((dirichlet_beta_odd_f64(1) - pi.powi(3) / 32.0).abs() < 1e-15);
+///     assert!((dirichlet_beta_odd_f64(12) - 0.9999999999988197).abs() < 1e-16);
+///     assert_eq!(dirichlet_beta_odd_f64(100), 1.0);
+///
+pub fn dirichlet_beta_odd_f64(n: u64) -> f64 {
+    if n < DIRECT_BETA_THRESHOLD {
+        let (q, p) = dirichlet_beta_odd(n);
+        return rational_to_f64(&q) * ::std::f64::consts::PI.powi(p as i32);
+    }
+    let s = (2 * n + 1).min(i32::MAX as u64) as i32;
+    let mut sum = 0.0;
+    for k in (1..12).rev() {
+        let term = f64::from(2 * k + 1).powi(-s);
+        sum += if k % 2 == 1 { -term } else { term };
+    }
+    1.0 + sum
+}